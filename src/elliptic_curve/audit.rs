@@ -0,0 +1,495 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{Field, FieldElementTraits};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::montgomery::MontgomeryCurve;
+use crate::elliptic_curve::number_theory::{is_probable_prime, partial_factor, RHO_BUDGET};
+use crate::elliptic_curve::twist::{rho_bits, TwistReport};
+
+
+// Security audit in the spirit of SafeCurves (Bernstein and Lange,
+// https://safecurves.cr.yp.to). Criteria that need a factorisation the
+// budgeted Pollard rho cannot finish are reported as unknown rather than
+// guessed. Rigidity and indistinguishability are about how the curve was
+// chosen and encoded, so they are not checked.
+
+
+// Pollard rho on the prime order subgroup and on the twist must cost 2^100
+pub const RHO_SECURITY_BITS: f64 = 100.0;
+
+// |D| of the CM field must exceed 2^100
+pub const CM_DISCRIMINANT_BITS: u64 = 100;
+
+// When n - 1 cannot be factored, embedding degrees up to this are searched
+pub const TRANSFER_SEARCH: u64 = 1 << 12;
+
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Criterion{
+    // p is an odd prime above 3
+    Field,
+    // 4a^3 + 27b^2 != 0
+    Discriminant,
+    // |t| <= 2 sqrt(p)
+    Hasse,
+    // #E = h n with n prime, and [n]G = O for a declared generator
+    Order,
+    Rho,
+    // MOV / Frey-Ruck: embedding degree at least (n - 1) / 100
+    Transfer,
+    // Smart's attack needs #E = p, that is t = 1
+    Anomalous,
+    CmDiscriminant,
+    Twist,
+    // a Montgomery form, so the x-only ladder applies
+    Ladder,
+    // a complete Edwards form, d not a square
+    Complete,
+}
+
+
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Verdict{
+    Pass,
+    Fail,
+    Unknown,
+}
+
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct Check{
+    criterion: Criterion,
+    verdict: Verdict,
+    detail: String,
+}
+
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct AuditReport{
+    prime: BigInt,
+    group_order: Option<BigInt>,
+    subgroup_order: Option<BigInt>,
+    embedding_degree: Option<BigInt>,
+    cm_discriminant: Option<BigInt>,
+    twist: Option<TwistReport>,
+    checks: Vec<Check>,
+}
+
+
+// Criteria that need arithmetic in a prime field
+const FIELD_DEPENDENT: [Criterion;9] = [
+    Criterion::Hasse,
+    Criterion::Order,
+    Criterion::Rho,
+    Criterion::Transfer,
+    Criterion::Anomalous,
+    Criterion::CmDiscriminant,
+    Criterion::Twist,
+    Criterion::Ladder,
+    Criterion::Complete,
+];
+
+
+impl EllipticCurve{
+
+    // Counts points unless the curve declares its order and cofactor. Without
+    // them only fields up to about 64 bits are practical, Schoof's algorithm
+    // does not finish on 256-bit fields (see counting::schoof).
+    pub fn audit(&self) -> Result<AuditReport,FieldErrors>{
+        let p = self.get_prime().clone();
+        let mut report = AuditReport {
+            prime: p.clone(),
+            group_order: None,
+            subgroup_order: None,
+            embedding_degree: None,
+            cm_discriminant: None,
+            twist: None,
+            checks: vec![],
+        };
+
+        let prime = p > BigInt::from(3) && is_probable_prime(&p);
+        report.push(Criterion::Field,Verdict::from(prime),format!("p = {} ({} bits)",p,p.bits()));
+        let (a,b) = (self.get_a().get_number(),self.get_b().get_number());
+        let discriminant: BigInt = (a * a * a * 4 + b * b * 27) % &p;
+        report.push(Criterion::Discriminant,Verdict::from(!discriminant.is_zero()),format!("4a^3 + 27b^2 = {} mod p",discriminant));
+        if !prime{
+            for criterion in FIELD_DEPENDENT{
+                report.push(criterion,Verdict::Unknown,"needs a prime field with p > 3".to_string());
+            }
+            return Ok(report);
+        }
+
+        let order = self.group_order()?;
+        let trace: BigInt = &p + 1 - &order;
+        report.push(Criterion::Hasse,Verdict::from(&trace * &trace <= &p * 4),format!("#E = {}, t = {}",order,trace));
+        report.group_order = Some(order.clone());
+
+        let n = self.audit_order(&mut report,&order);
+        match &n{
+            Some(n) => {
+                let bits = rho_bits(n);
+                report.push(Criterion::Rho,Verdict::from(bits >= RHO_SECURITY_BITS),format!("{:.1} bits",bits));
+                self.audit_transfer(&mut report,n);
+            },
+            None => {
+                report.push(Criterion::Rho,Verdict::Unknown,"n is not known".to_string());
+                report.push(Criterion::Transfer,Verdict::Unknown,"n is not known".to_string());
+            },
+        }
+        report.push(Criterion::Anomalous,Verdict::from(!trace.is_one()),format!("t = {}",trace));
+
+        let (discriminant,exact) = cm_discriminant(&p,&trace);
+        let large = discriminant.bits() > CM_DISCRIMINANT_BITS;
+        let (verdict,detail) = match (exact,large){
+            (true,_) => (Verdict::from(large),format!("D = -{} ({} bits)",discriminant,discriminant.bits())),
+            // only an upper bound, still decisive when it is small
+            (false,false) => (Verdict::Fail,format!("|D| <= {}",discriminant)),
+            (false,true) => (Verdict::Unknown,format!("|D| <= 2^{}, 4p - t^2 is not fully factored",discriminant.bits())),
+        };
+        report.push(Criterion::CmDiscriminant,verdict,detail);
+        if exact{
+            report.cm_discriminant = Some(-discriminant);
+        }
+
+        let twist = self.twist_report()?;
+        let verdict = match twist.rho_security_bits(){
+            Some(bits) => Verdict::from(bits >= RHO_SECURITY_BITS),
+            None if twist.rho_security_bound() < RHO_SECURITY_BITS => Verdict::Fail,
+            None => Verdict::Unknown,
+        };
+        report.push(Criterion::Twist,verdict,twist.to_string());
+        report.twist = Some(twist);
+
+        self.audit_models(&mut report)?;
+        Ok(report)
+    }
+
+    // The prime order subgroup, declared or the largest prime factor of #E
+    fn audit_order(&self, report:&mut AuditReport, order:&BigInt) -> Option<BigInt>{
+        let n = match self.get_order(){
+            Some(n) => n.clone(),
+            None => {
+                let (factors,unfactored) = partial_factor(order,RHO_BUDGET);
+                if !unfactored.is_one(){
+                    report.push(Criterion::Order,Verdict::Unknown,format!("#E = {} is not fully factored",order));
+                    return None;
+                }
+                factors.last().map(|(q,_)| q.clone()).unwrap_or(BigInt::one())
+            },
+        };
+        let cofactor = order / &n;
+        let mut problems = vec![];
+        if !is_probable_prime(&n){
+            problems.push("n is not prime");
+        }
+        if !(order % &n).is_zero(){
+            problems.push("n does not divide #E");
+        }
+        if let Some(generator) = self.get_generator(){
+            if !self.multiply(generator,&n).is_identity(){
+                problems.push("[n]G != O");
+            }
+        }
+        let mut detail = format!("n = {} ({} bits), h = {}",n,n.bits(),cofactor);
+        if !problems.is_empty(){
+            detail = format!("{}, {}",detail,problems.join(", "));
+        }
+        report.push(Criterion::Order,Verdict::from(problems.is_empty()),detail);
+        report.subgroup_order = Some(n.clone());
+        Some(n)
+    }
+
+    // The multiplicative order k of p mod n, exact when n - 1 factors
+    fn audit_transfer(&self, report:&mut AuditReport, n:&BigInt){
+        let p = self.get_prime();
+        if (p % n).is_zero(){
+            report.push(Criterion::Transfer,Verdict::Unknown,"n = p has no embedding degree".to_string());
+            return;
+        }
+        let threshold: BigInt = (n - 1) / 100;
+        let k = multiplicative_order(p,n).or_else(|| self.embedding_degree(n,TRANSFER_SEARCH).map(BigInt::from));
+        match k{
+            Some(k) => {
+                report.push(Criterion::Transfer,Verdict::from(k >= threshold),format!("k = {}",k));
+                report.embedding_degree = Some(k);
+            },
+            None if threshold <= BigInt::from(TRANSFER_SEARCH) => {
+                report.push(Criterion::Transfer,Verdict::Pass,format!("k > {}",TRANSFER_SEARCH));
+            },
+            None => {
+                report.push(Criterion::Transfer,Verdict::Unknown,format!("k > {}, n - 1 is not fully factored",TRANSFER_SEARCH));
+            },
+        }
+    }
+
+    // Montgomery forms come from points (alpha, 0) with 3 alpha^2 + a = 1 / s^2,
+    // giving A = 3 alpha s and B = s. One with A^2 - 4 not a square has a single
+    // point of order two and is birational to a complete Edwards curve.
+    fn audit_models(&self, report:&mut AuditReport) -> Result<(),FieldErrors>{
+        let mut forms = vec![];
+        for alpha in self.two_torsion_x(){
+            let slope = alpha.square() * alpha.integer_like(3) + self.get_a();
+            if let Some(root) = slope.sqrt(){
+                let s = root.modinv();
+                forms.push(MontgomeryCurve::new(alpha.integer_like(3) * &alpha * &s,s)?);
+            }
+        }
+        let describe = |m:&MontgomeryCurve<FieldElement>| format!("B y^2 = x^3 + A x^2 + x with A = {}, B = {}",m.get_a().get_number(),m.get_b().get_number());
+
+        match forms.first(){
+            Some(form) => report.push(Criterion::Ladder,Verdict::Pass,describe(form)),
+            None if self.two_torsion_x().is_empty() => report.push(Criterion::Ladder,Verdict::Fail,"no point of order two".to_string()),
+            None => report.push(Criterion::Ladder,Verdict::Fail,"3 alpha^2 + a is not a square for any point (alpha, 0)".to_string()),
+        }
+        let complete = forms.iter().find(|m| !(m.get_a().square() - m.get_a().integer_like(4)).is_square());
+        match complete{
+            Some(form) => report.push(Criterion::Complete,Verdict::Pass,describe(form)),
+            None => report.push(Criterion::Complete,Verdict::Fail,"no Montgomery form with A^2 - 4 a non-square".to_string()),
+        }
+        Ok(())
+    }
+
+}
+
+
+// Smallest k with p^k = 1 mod n, when n - 1 can be factored
+fn multiplicative_order(p:&BigInt, n:&BigInt) -> Option<BigInt>{
+    let (factors,unfactored) = partial_factor(&(n - 1),RHO_BUDGET);
+    if !unfactored.is_one(){
+        return None;
+    }
+    let mut k: BigInt = n - 1;
+    for (q,e) in factors{
+        for _ in 0..e{
+            let candidate = &k / &q;
+            if !p.modpow(&candidate,n).is_one(){
+                break;
+            }
+            k = candidate;
+        }
+    }
+    Some(k)
+}
+
+
+// |D| for the fundamental discriminant D of t^2 - 4p = f^2 D, and whether it
+// is exact. The squarefree part of 4p - t^2 is read off its factorisation, a
+// leftover composite that is not a square is kept whole, giving an upper bound.
+fn cm_discriminant(p:&BigInt, t:&BigInt) -> (BigInt,bool){
+    let s: BigInt = p * 4 - t * t;
+    let (factors,unfactored) = partial_factor(&s,RHO_BUDGET);
+    let mut squarefree = factors.iter()
+        .filter(|(_,e)| e % 2 == 1)
+        .fold(BigInt::one(),|acc,(q,_)| acc * q);
+    let root = unfactored.sqrt();
+    let exact = &root * &root == unfactored;
+    if !exact{
+        squarefree *= unfactored;
+    }
+    // -m is a discriminant when m = 3 mod 4, otherwise -4m is
+    if &squarefree % 4 == BigInt::from(3){
+        (squarefree,exact)
+    }
+    else{
+        (squarefree * 4,exact)
+    }
+}
+
+
+impl From<bool> for Verdict{
+    fn from(pass:bool) -> Self{
+        if pass { Verdict::Pass } else { Verdict::Fail }
+    }
+}
+
+
+impl Check{
+
+    pub fn get_criterion(&self) -> Criterion{
+        self.criterion
+    }
+
+    pub fn get_verdict(&self) -> Verdict{
+        self.verdict
+    }
+
+    pub fn get_detail(&self) -> &str{
+        &self.detail
+    }
+
+}
+
+
+impl AuditReport{
+
+    fn push(&mut self, criterion:Criterion, verdict:Verdict, detail:String){
+        self.checks.push(Check { criterion, verdict, detail });
+    }
+
+    pub fn get_prime(&self) -> &BigInt{
+        &self.prime
+    }
+
+    pub fn get_group_order(&self) -> Option<&BigInt>{
+        self.group_order.as_ref()
+    }
+
+    pub fn get_subgroup_order(&self) -> Option<&BigInt>{
+        self.subgroup_order.as_ref()
+    }
+
+    pub fn get_embedding_degree(&self) -> Option<&BigInt>{
+        self.embedding_degree.as_ref()
+    }
+
+    // D < 0, when 4p - t^2 could be factored far enough
+    pub fn get_cm_discriminant(&self) -> Option<&BigInt>{
+        self.cm_discriminant.as_ref()
+    }
+
+    pub fn get_twist(&self) -> Option<&TwistReport>{
+        self.twist.as_ref()
+    }
+
+    pub fn get_checks(&self) -> &Vec<Check>{
+        &self.checks
+    }
+
+    pub fn get(&self, criterion:Criterion) -> Option<&Check>{
+        self.checks.iter().find(|check| check.criterion == criterion)
+    }
+
+    pub fn verdict(&self, criterion:Criterion) -> Verdict{
+        self.get(criterion).map(|check| check.verdict).unwrap_or(Verdict::Unknown)
+    }
+
+    // every criterion passed
+    pub fn is_safe(&self) -> bool{
+        self.checks.iter().all(|check| check.verdict == Verdict::Pass)
+    }
+
+}
+
+
+impl fmt::Display for Criterion{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let name = match self{
+            Criterion::Field => "field",
+            Criterion::Discriminant => "discriminant",
+            Criterion::Hasse => "hasse",
+            Criterion::Order => "order",
+            Criterion::Rho => "rho",
+            Criterion::Transfer => "transfer",
+            Criterion::Anomalous => "anomalous",
+            Criterion::CmDiscriminant => "cm",
+            Criterion::Twist => "twist",
+            Criterion::Ladder => "ladder",
+            Criterion::Complete => "complete",
+        };
+        write!(f,"{}",name)
+    }
+}
+
+
+impl fmt::Display for Verdict{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let name = match self{
+            Verdict::Pass => "pass",
+            Verdict::Fail => "FAIL",
+            Verdict::Unknown => "unknown",
+        };
+        write!(f,"{}",name)
+    }
+}
+
+
+impl fmt::Display for AuditReport{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        for check in self.checks.iter(){
+            writeln!(f,"{:<13}{:<9}{}",check.criterion.to_string(),check.verdict.to_string(),check.detail)?;
+        }
+        Ok(())
+    }
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::small_curve;
+
+
+#[test]
+fn audit_secp256k1(){
+    use crate::elliptic_curve::curves::secp256k1;
+
+    let report = secp256k1().audit().unwrap();
+    for criterion in [Criterion::Field,Criterion::Discriminant,Criterion::Hasse,Criterion::Order,Criterion::Rho,Criterion::Anomalous,Criterion::Twist]{
+        assert_eq!(report.verdict(criterion),Verdict::Pass,"{}",criterion);
+    }
+    assert_ne!(report.verdict(Criterion::Transfer),Verdict::Fail);
+    // j = 0, the CM field is Q(sqrt(-3))
+    assert_eq!(report.verdict(Criterion::CmDiscriminant),Verdict::Fail);
+    assert_eq!(report.get_cm_discriminant(),Some(&BigInt::from(-3)));
+    // prime order, so no point of order two for a Montgomery form
+    assert_eq!(report.verdict(Criterion::Ladder),Verdict::Fail);
+    assert_eq!(report.verdict(Criterion::Complete),Verdict::Fail);
+    assert!(!report.is_safe());
+    assert_eq!(report.get_checks().len(),11);
+    assert!(report.to_string().lines().any(|line| line.starts_with("cm") && line.contains("FAIL") && line.contains("D = -3")));
+}
+
+
+#[test]
+fn audit_weak_curves(){
+    // 1031 points over GF(1031)
+    let report = small_curve(4,2,1031).audit().unwrap();
+    assert_eq!(report.get_group_order(),Some(&BigInt::from(1031)));
+    assert_eq!(report.verdict(Criterion::Anomalous),Verdict::Fail);
+    assert_eq!(report.verdict(Criterion::Order),Verdict::Pass);
+    assert_eq!(report.verdict(Criterion::Rho),Verdict::Fail);
+    assert_eq!(report.verdict(Criterion::Transfer),Verdict::Unknown);
+
+    // supersingular y^2 = x^3 + 1 over p = 2 mod 3 has p + 1 = 6 * 16699 points
+    // and embedding degree 2
+    let report = small_curve(0,1,100193).audit().unwrap();
+    assert_eq!(report.get_subgroup_order(),Some(&BigInt::from(16699)));
+    assert_eq!(report.get_embedding_degree(),Some(&BigInt::from(2)));
+    assert_eq!(report.verdict(Criterion::Transfer),Verdict::Fail);
+    assert_eq!(report.verdict(Criterion::Anomalous),Verdict::Pass);
+
+    let p = BigInt::from(1001);
+    let composite = EllipticCurve::new(FieldElement::new(BigInt::from(2),p.clone()).unwrap(),FieldElement::new(BigInt::from(3),p).unwrap()).unwrap();
+    let report = composite.audit().unwrap();
+    assert_eq!(report.verdict(Criterion::Field),Verdict::Fail);
+    assert_eq!(report.verdict(Criterion::Discriminant),Verdict::Pass);
+    assert!(report.get_checks().iter().skip(2).all(|check| check.get_verdict() == Verdict::Unknown));
+}
+
+
+#[test]
+fn audit_montgomery_models(){
+    use crate::elliptic_curve::maps::MontgomeryWeierstrassMap;
+
+    // x^2 + y^2 = 1 + d x^2 y^2 with d a non-residue is complete, its Montgomery
+    // form has A = 2 (1 + d) / (1 - d), B = 4 / (1 - d)
+    let p = BigInt::from(1009);
+    let element = |n:i32| FieldElement::new_from_i32(n,p.clone()).unwrap();
+    let d = (2..).map(element).find(|d| !d.is_square()).unwrap();
+    let one = element(1);
+    let montgomery = MontgomeryCurve::new(element(2) * (one.clone() + &d) / (one.clone() - &d),element(4) / (one - &d)).unwrap();
+    let curve = MontgomeryWeierstrassMap::new(montgomery).weierstrass_curve().unwrap();
+    let report = curve.audit().unwrap();
+    assert_eq!(report.verdict(Criterion::Ladder),Verdict::Pass);
+    assert_eq!(report.verdict(Criterion::Complete),Verdict::Pass);
+
+    // x^3 - 7x + 6 = (x - 1)(x - 2)(x + 3), with three points of order two every
+    // Montgomery form has A^2 - 4 a square
+    let report = small_curve(90,6,97).audit().unwrap();
+    assert_eq!(report.verdict(Criterion::Ladder),Verdict::Pass);
+    assert_eq!(report.verdict(Criterion::Complete),Verdict::Fail);
+}
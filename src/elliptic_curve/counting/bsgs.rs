@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use num_bigint::BigInt;
+use num_traits::One;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{Field, FieldElementTraits};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::counting::order_of_point;
+use crate::elliptic_curve::number_theory::{lcm, SplitMix64};
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Give up after this many points on each of the curve and its twist, Mestre's
+// theorem makes it very unlikely to be reached for p > 229.
+const MAX_ROUNDS: usize = 64;
+
+
+// Hasse interval [p + 1 - 2 sqrt(p), p + 1 + 2 sqrt(p)] holding #E(Fp)
+pub fn hasse_interval(p:&BigInt) -> (BigInt,BigInt){
+    let width = (p * BigInt::from(4)).sqrt();
+    (p + 1 - &width,p + 1 + width)
+}
+
+
+// Trace of Frobenius t = p + 1 - #E(Fp) for p up to 2^64. The order of random
+// points narrows #E to the multiples of L in the Hasse interval, once L has a
+// single multiple there it is #E. When the group is too far from cyclic for
+// that, points on the quadratic twist E' settle #E = 2p + 2 - #E' instead.
+pub fn trace_by_bsgs(curve:&EllipticCurve) -> Result<BigInt,FieldErrors>{
+    let p = curve.get_prime();
+    if p.bits() > 64 || p <= &BigInt::from(229){
+        return Err(FieldErrors::InvalidParams("Baby-step giant-step counting needs 229 < p < 2^64".to_string()));
+    }
+    let twist = quadratic_twist(curve)?;
+    let (lo,hi) = hasse_interval(p);
+    let mut sampler = PointSampler::new(p);
+    let (mut exponent,mut twist_exponent) = (BigInt::one(),BigInt::one());
+
+    for _ in 0..MAX_ROUNDS{
+        exponent = lcm(&exponent,&random_point_order(curve,&exponent,&lo,&hi,&mut sampler));
+        if let Some(order) = unique_multiple(&exponent,&lo,&hi){
+            return Ok(p + 1 - order);
+        }
+        twist_exponent = lcm(&twist_exponent,&random_point_order(&twist,&twist_exponent,&lo,&hi,&mut sampler));
+        if let Some(twist_order) = unique_multiple(&twist_exponent,&lo,&hi){
+            return Ok(twist_order - p - 1);
+        }
+    }
+    Err(FieldErrors::InvalidParams("Point orders did not determine the group order".to_string()))
+}
+
+
+// y^2 = x^3 + a d^2 x + b d^3 for the smallest non-residue d
+fn quadratic_twist(curve:&EllipticCurve) -> Result<EllipticCurve,FieldErrors>{
+    curve.quadratic_twist(&curve.get_a().non_residue().unwrap())
+}
+
+
+fn unique_multiple(exponent:&BigInt, lo:&BigInt, hi:&BigInt) -> Option<BigInt>{
+    let first = (lo + exponent - 1) / exponent;
+    let last = hi / exponent;
+    if first == last { Some(first * exponent) } else { None }
+}
+
+
+// Order of a random point P, using that #E is a multiple of the exponent found
+// so far: search k with [k] ([exponent] P) = O over the multiples in range.
+fn random_point_order(curve:&EllipticCurve, exponent:&BigInt, lo:&BigInt, hi:&BigInt, sampler:&mut PointSampler) -> BigInt{
+    let point = sampler.point(curve);
+    let q = point.multiply(exponent);
+    let first = (lo + exponent - 1) / exponent;
+    let last = hi / exponent;
+    match multiple_in_interval(&q,&first,&last){
+        Some(k) => order_of_point(&point,&(k * exponent)),
+        None => BigInt::one(),
+    }
+}
+
+
+// Smallest k in [lo, hi] with [k] point = O, from baby steps [j] point with
+// j < s and giant steps [lo + i s] point.
+pub fn multiple_in_interval(point:&PointAffine<FieldElement>, lo:&BigInt, hi:&BigInt) -> Option<BigInt>{
+    if lo > hi{
+        return None;
+    }
+    let width: BigInt = hi - lo + 1;
+    let s = width.sqrt() + 1;
+    let key = |r:&PointAffine<FieldElement>| (r.is_identity(),r.get_x().get_number().clone(),r.get_y().get_number().clone());
+
+    let mut baby_steps = HashMap::new();
+    let mut r = PointAffine::identity(point.get_a().clone(),point.get_b().clone());
+    let mut j = BigInt::from(0);
+    while j < s{
+        baby_steps.entry(key(&r)).or_insert(j.clone());
+        r = r.add(point.clone());
+        j += 1;
+    }
+
+    // r is now [s] point
+    let mut giant = point.multiply(lo);
+    let mut k = lo.clone();
+    while &k <= hi{
+        if let Some(j) = baby_steps.get(&key(&giant.negate())){
+            let candidate = &k + j;
+            return if &candidate <= hi { Some(candidate) } else { None };
+        }
+        giant = giant.add(r.clone());
+        k += &s;
+    }
+    None
+}
+
+
+// random points from a fixed seed so counts are reproducible
+struct PointSampler{
+    rng: SplitMix64,
+    p: BigInt,
+}
+
+impl PointSampler{
+
+    fn new(p:&BigInt) -> Self{
+        PointSampler { rng: SplitMix64::new(0x853c49e6748fea9b), p: p.clone() }
+    }
+
+    fn point(&mut self, curve:&EllipticCurve) -> PointAffine<FieldElement>{
+        let (a,b) = (curve.get_a(),curve.get_b());
+        loop{
+            let x = FieldElement::new(self.rng.below(&self.p),self.p.clone()).unwrap();
+            let rhs = x.square() * &x + a.clone() * &x + b;
+            if rhs.is_zero(){
+                continue;
+            }
+            if let Some(y) = rhs.sqrt(){
+                return PointAffine::new(x,y,a.clone(),b.clone());
+            }
+        }
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::curve_from_u64;
+
+
+#[test]
+fn bsgs_matches_legendre(){
+    use crate::elliptic_curve::counting::enumeration::order_by_legendre;
+
+    for (a,b,p) in [(2,3,1009),(0,7,10007),(1,1,65537),(123,456,65537),(5,1,1_000_003),(0,1,1_000_003),(1,0,65537),(3,0,1_000_003)]{
+        let curve = curve_from_u64(a,b,p);
+        let order = order_by_legendre(&curve).unwrap();
+        assert_eq!(BigInt::from(p + 1) - trace_by_bsgs(&curve).unwrap(),order);
+    }
+    assert!(trace_by_bsgs(&curve_from_u64(2,3,227)).is_err());
+}
+
+
+#[test]
+fn bsgs_counts_64_bit_curves(){
+    // y^2 = x^3 + b is supersingular when p = 2 mod 3
+    let p = 281474976710597_u64;
+    assert_eq!(curve_from_u64(0,7,p).trace_of_frobenius().unwrap(),BigInt::from(0));
+
+    // for p = 1 mod 3 the CM by Z[w] forces 4p = t^2 + 3v^2
+    let p = 18446744073709551427_u64;
+    let t = curve_from_u64(0,7,p).trace_of_frobenius().unwrap();
+    let v2: BigInt = (BigInt::from(4) * p - &t * &t) / 3;
+    assert_eq!(v2.sqrt().pow(2),v2);
+
+    let p = 281474976710563_u64;
+    let curve = curve_from_u64(3,0x5eed,p);
+    let order = curve.order().unwrap();
+    let (lo,hi) = hasse_interval(&BigInt::from(p));
+    assert!(lo <= order && order <= hi);
+    let mut sampler = PointSampler::new(curve.get_prime());
+    for _ in 0..4{
+        assert!(sampler.point(&curve).multiply(&order).is_identity());
+    }
+    let twist = quadratic_twist(&curve).unwrap();
+    let twist_order = BigInt::from(2) * p + 2 - &order;
+    for _ in 0..4{
+        assert!(sampler.point(&twist).multiply(&twist_order).is_identity());
+    }
+}
+
+
+#[test]
+fn baby_step_giant_step_interval(){
+    let curve = curve_from_u64(0,3,7);
+    let point = curve.point(BigInt::from(3),BigInt::from(3)).unwrap();
+    assert_eq!(multiple_in_interval(&point,&BigInt::from(1),&BigInt::from(100)),Some(BigInt::from(13)));
+    assert_eq!(multiple_in_interval(&point,&BigInt::from(14),&BigInt::from(40)),Some(BigInt::from(26)));
+    assert_eq!(multiple_in_interval(&point,&BigInt::from(27),&BigInt::from(38)),None);
+}
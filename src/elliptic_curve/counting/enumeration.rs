@@ -0,0 +1,207 @@
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{Field, FieldElementTraits};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::counting::{order_from_factors, GroupStructure};
+use crate::elliptic_curve::number_theory::{factor, jacobi, lcm};
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Every point of the curve, the identity first and then by increasing x, with
+// the smaller y of each pair first.
+pub struct Points<'a>{
+    curve: &'a EllipticCurve,
+    x: BigInt,
+    pending: Option<PointAffine<FieldElement>>,
+    started: bool,
+}
+
+
+impl Iterator for Points<'_>{
+    type Item = PointAffine<FieldElement>;
+
+    fn next(&mut self) -> Option<Self::Item>{
+        if !self.started{
+            self.started = true;
+            return Some(self.curve.identity());
+        }
+        if let Some(point) = self.pending.take(){
+            return Some(point);
+        }
+        let (a,b) = (self.curve.get_a(),self.curve.get_b());
+        while &self.x < self.curve.get_prime(){
+            let x = FieldElement::new(self.x.clone(),self.curve.get_prime().clone()).unwrap();
+            self.x += 1;
+            let rhs = x.square() * &x + a.clone() * &x + b;
+            if let Some(y) = rhs.sqrt(){
+                let (y1,y2) = if y.get_number() <= y.negate().get_number() { (y.clone(),y.negate()) } else { (y.negate(),y) };
+                if !y1.is_zero(){
+                    self.pending = Some(PointAffine::new(x.clone(),y2,a.clone(),b.clone()));
+                }
+                return Some(PointAffine::new(x,y1,a.clone(),b.clone()));
+            }
+        }
+        None
+    }
+}
+
+
+// #E = 1 + sum over x of the number of y with y^2 = x^3 + ax + b
+pub fn order_by_enumeration(curve:&EllipticCurve) -> BigInt{
+    let (a,b) = (curve.get_a(),curve.get_b());
+    let mut order = BigInt::one();
+    let mut x = FieldElement::zero(curve.get_prime().clone());
+    let mut i = BigInt::zero();
+    while &i < curve.get_prime(){
+        let rhs = x.square() * &x + a.clone() * &x + b;
+        if rhs.is_zero(){
+            order += 1;
+        }
+        else if rhs.is_square(){
+            order += 2;
+        }
+        x = x.clone() + x.one_like();
+        i += 1;
+    }
+    order
+}
+
+
+// #E = p + 1 + sum over x of (x^3 + ax + b / p), for odd p below 2^32
+pub fn order_by_legendre(curve:&EllipticCurve) -> Result<BigInt,FieldErrors>{
+    let p = match curve.get_prime().to_u64(){
+        Some(p) if p % 2 == 1 && p < 1 << 32 => p,
+        _ => return Err(FieldErrors::InvalidParams("Legendre symbol counting needs an odd prime below 2^32".to_string())),
+    };
+    let a = curve.get_a().get_number().to_u64().unwrap();
+    let b = curve.get_b().get_number().to_u64().unwrap();
+
+    let mut sum = 0_i64;
+    for x in 0..p{
+        let rhs = ((x * x % p) * x % p + a * x % p + b) % p;
+        sum += jacobi(rhs,p) as i64;
+    }
+    Ok(BigInt::from(p) + 1 + sum)
+}
+
+
+impl EllipticCurve{
+
+    pub fn points(&self) -> Points<'_>{
+        Points { curve: self, x: BigInt::zero(), pending: None, started: false }
+    }
+
+    pub fn point_order(&self, point:&PointAffine<FieldElement>) -> Result<BigInt,FieldErrors>{
+        if !self.contains(point){
+            return Err(FieldErrors::PointNotOnCurve("Point is not on the curve".to_string()));
+        }
+        let order = match self.get_order(){
+            Some(order) if point.multiply(order).is_identity() => order.clone(),
+            _ => self.order()?,
+        };
+        Ok(order_from_factors(point,&order,&factor(&order)))
+    }
+
+    // The exponent n1 is the lcm of all point orders, found by walking the
+    // points until it reaches #E or the points run out.
+    pub fn group_structure(&self) -> Result<GroupStructure,FieldErrors>{
+        let order = self.order()?;
+        let factors = factor(&order);
+        let mut exponent = BigInt::one();
+        for point in self.points(){
+            if exponent == order{
+                break;
+            }
+            exponent = lcm(&exponent,&order_from_factors(&point,&order,&factors));
+        }
+        let n2 = &order / &exponent;
+        Ok(GroupStructure::new(exponent,n2))
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::small_curve;
+
+
+#[test]
+fn enumerate_small_curve(){
+    let curve = small_curve(0,3,7);
+    let points: Vec<_> = curve.points().collect();
+
+    assert_eq!(points.len(),13);
+    assert!(points[0].is_identity());
+    assert_eq!(points[1],curve.point(BigInt::from(1),BigInt::from(2)).unwrap());
+    assert_eq!(points[2],curve.point(BigInt::from(1),BigInt::from(5)).unwrap());
+    for (i,p1) in points.iter().enumerate(){
+        assert!(curve.contains(p1));
+        assert!(points[i + 1..].iter().all(|p2| p2 != p1));
+    }
+    assert_eq!(curve.order().unwrap(),BigInt::from(13));
+}
+
+
+#[test]
+fn enumeration_matches_legendre(){
+    for (a,b,p) in [(0,3,7),(2,3,97),(1,1,23),(4,6,11),(3,2,1009),(2,1,5)]{
+        let curve = small_curve(a,b,p);
+        let order = order_by_enumeration(&curve);
+        assert_eq!(order_by_legendre(&curve).unwrap(),order);
+        assert_eq!(BigInt::from(curve.points().count()),order);
+    }
+}
+
+
+#[test]
+fn legendre_counts_medium_fields(){
+    // y^2 = x^3 + b is supersingular with p + 1 points when p = 2 mod 3
+    let curve = small_curve(0,7,10007);
+    assert_eq!(curve.order().unwrap(),BigInt::from(10008));
+
+    let p = 1_000_003_i64;
+    let curve = small_curve(0,7,p as i32);
+    let order = curve.order().unwrap();
+    let trace = BigInt::from(p + 1) - &order;
+    assert!(&trace * &trace <= BigInt::from(4 * p));
+}
+
+
+#[test]
+fn point_orders(){
+    let curve = small_curve(4,6,11);
+    // the roots 1, 2 and 8 of x^3 + 4x + 6 give the points of order two
+    for x in [1,2,8]{
+        let point = curve.point(BigInt::from(x),BigInt::zero()).unwrap();
+        assert_eq!(curve.point_order(&point).unwrap(),BigInt::from(2));
+    }
+    assert_eq!(curve.point_order(&curve.identity()).unwrap(),BigInt::one());
+
+    let orders: Vec<BigInt> = curve.points().map(|point| curve.point_order(&point).unwrap()).collect();
+    assert_eq!(orders.iter().max().unwrap(),&BigInt::from(8));
+    assert!(orders.iter().all(|order| (BigInt::from(16) % order).is_zero()));
+}
+
+
+#[test]
+fn group_structures(){
+    let cases = [((0,3,7),13,1),((4,6,11),8,2),((2,3,97),50,2),((0,3,13),3,3),((0,1,31),6,6),((0,1,37),12,4)];
+    for ((a,b,p),n1,n2) in cases{
+        let structure = small_curve(a,b,p).group_structure().unwrap();
+        assert_eq!(structure,GroupStructure::new(BigInt::from(n1),BigInt::from(n2)));
+        assert_eq!(structure.is_cyclic(),n2 == 1);
+    }
+    assert_eq!(small_curve(0,3,13).group_structure().unwrap().to_string(),"Z/3 x Z/3");
+
+    // b = 0: (0, 0) is an ordinary point of order two, x^2 + 1 has no root mod 11
+    let curve = small_curve(1,0,11);
+    assert_eq!(curve.points().count(),12);
+    assert_eq!(curve.points().filter(|point| point.is_identity()).count(),1);
+    assert_eq!(curve.group_structure().unwrap(),GroupStructure::new(BigInt::from(12),BigInt::one()));
+}
@@ -0,0 +1,159 @@
+pub mod enumeration;
+pub mod bsgs;
+pub mod schoof;
+
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::number_theory::factor;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::counting::enumeration::{order_by_enumeration, order_by_legendre};
+use crate::elliptic_curve::counting::bsgs::trace_by_bsgs;
+use crate::elliptic_curve::counting::schoof::order_by_schoof;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Fields up to this size are counted by listing points, up to LEGENDRE_LIMIT
+// by summing Legendre symbols, up to 2^64 by baby-step giant-step and up to
+// SCHOOF_LIMIT_BITS by Schoof's algorithm. Larger curves need a declared order.
+pub const ENUMERATION_LIMIT: u64 = 1 << 10;
+pub const LEGENDRE_LIMIT: u64 = 1 << 20;
+pub const SCHOOF_LIMIT_BITS: u64 = 72;
+
+
+impl EllipticCurve{
+
+    // #E(Fp) by the cheapest method available for the size of the field.
+    // Schoof's algorithm takes about five minutes in release mode at 72 bits
+    // and does not finish at cryptographic sizes (see counting::schoof), so
+    // larger fields are refused. Use `group_order` with a declared order and
+    // cofactor there.
+    pub fn order(&self) -> Result<BigInt,FieldErrors>{
+        let p = self.get_prime();
+        if p <= &BigInt::from(ENUMERATION_LIMIT){
+            return Ok(order_by_enumeration(self));
+        }
+        if p <= &BigInt::from(LEGENDRE_LIMIT){
+            return order_by_legendre(self);
+        }
+        if p.bits() <= 64{
+            return Ok(p + 1 - trace_by_bsgs(self)?);
+        }
+        if p.bits() > SCHOOF_LIMIT_BITS{
+            return Err(FieldErrors::InvalidParams(format!("Counting points over a {}-bit field is out of reach, declare the order with with_generator",p.bits())));
+        }
+        order_by_schoof(self)
+    }
+
+    // #E(Fp) = h n from the declared subgroup order and cofactor, counted when
+    // either is missing
+    pub fn group_order(&self) -> Result<BigInt,FieldErrors>{
+        match (self.get_order(),self.get_cofactor()){
+            (Some(order),Some(cofactor)) => Ok(order * cofactor),
+            _ => self.order(),
+        }
+    }
+
+    // t = p + 1 - #E(Fp), bounded by |t| <= 2 sqrt(p)
+    pub fn trace_of_frobenius(&self) -> Result<BigInt,FieldErrors>{
+        Ok(self.get_prime() + 1 - self.group_order()?)
+    }
+
+}
+
+
+// Order of a point given any multiple of it, typically the group order.
+// Divides out each prime factor of the multiple while the point stays killed.
+pub fn order_of_point(point:&PointAffine<FieldElement>, multiple:&BigInt) -> BigInt{
+    order_from_factors(point,multiple,&factor(multiple))
+}
+
+
+pub(crate) fn order_from_factors(point:&PointAffine<FieldElement>, multiple:&BigInt, factors:&[(BigInt,u32)]) -> BigInt{
+    let mut order = multiple.clone();
+    for (q,_) in factors.iter(){
+        while (&order % q).is_zero() && point.multiply(&(&order / q)).is_identity(){
+            order /= q;
+        }
+    }
+    order
+}
+
+
+// E(Fp) is isomorphic to Z/n1 x Z/n2 with n2 dividing n1, n1 is the exponent
+// of the group and n2 = 1 exactly when the group is cyclic.
+#[derive(Debug,Clone,PartialEq)]
+pub struct GroupStructure{
+    n1: BigInt,
+    n2: BigInt,
+}
+
+
+impl GroupStructure{
+
+    pub fn new(n1:BigInt, n2:BigInt) -> Self{
+        GroupStructure { n1, n2 }
+    }
+
+    pub fn get_n1(&self) -> &BigInt{
+        &self.n1
+    }
+
+    pub fn get_n2(&self) -> &BigInt{
+        &self.n2
+    }
+
+    pub fn order(&self) -> BigInt{
+        &self.n1 * &self.n2
+    }
+
+    pub fn is_cyclic(&self) -> bool{
+        self.n2.is_one()
+    }
+
+}
+
+
+impl fmt::Display for GroupStructure{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        if self.is_cyclic(){
+            write!(f,"Z/{}",self.n1)
+        }
+        else{
+            write!(f,"Z/{} x Z/{}",self.n1,self.n2)
+        }
+    }
+}
+
+
+
+
+#[test]
+fn group_structure_display(){
+    assert_eq!(GroupStructure::new(BigInt::from(13),BigInt::one()).to_string(),"Z/13");
+    assert_eq!(GroupStructure::new(BigInt::from(6),BigInt::from(6)).to_string(),"Z/6 x Z/6");
+    assert_eq!(GroupStructure::new(BigInt::from(8),BigInt::from(2)).order(),BigInt::from(16));
+}
+
+
+#[test]
+fn trace_uses_the_declared_order(){
+    use num_traits::Num;
+    use crate::elliptic_curve::curves::secp256k1;
+
+    // counting a 256-bit curve is out of reach, the declared n h gives t at once
+    let t = BigInt::from_str_radix("14551231950B75FC4402DA1722FC9BAEF",16).unwrap();
+    assert_eq!(secp256k1().trace_of_frobenius().unwrap(),t);
+
+    // without the declared order the same curve is refused instead of counted
+    let curve = secp256k1();
+    let undeclared = EllipticCurve::new(curve.get_a().clone(),curve.get_b().clone()).unwrap();
+    assert!(undeclared.order().is_err());
+    assert!(undeclared.trace_of_frobenius().is_err());
+    assert!(undeclared.cofactor().is_err());
+}
@@ -0,0 +1,305 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::number_theory::is_probable_prime;
+use crate::polynomials::polynomial::Polynomial;
+use crate::polynomials::division::{curve_polynomial, DivisionPolynomials};
+
+
+// Schoof's algorithm. Frobenius (x, y) -> (x^p, y^p) satisfies
+// phi^2 - t phi + p = 0 on E[l], so t mod l can be read off the l-torsion,
+// which is described by the division polynomial psi_l. The residues for small
+// primes l are combined until their product exceeds the 4 sqrt(p) width of the
+// Hasse interval.
+//
+// The Elkies and Atkin refinements of SEA work with a degree (l - 1) / 2
+// factor of psi_l obtained from the modular polynomial Phi_l, which this crate
+// does not tabulate, so every l goes through the full psi_l of degree
+// (l^2 - 1) / 2. A 64-bit field needs l up to 31 and takes about a minute in
+// release mode, a 72-bit field about five minutes, which is where
+// `EllipticCurve::order` stops. Cryptographic sizes are out of reach: a
+// 256-bit field needs l up to about 100, with psi_l of degree near 5000, and
+// counting it is not feasible without Elkies and Atkin primes. Curves of that
+// size should come with their order declared, as the named curves do.
+
+
+type Poly = Polynomial<FieldElement>;
+
+
+pub fn order_by_schoof(curve:&EllipticCurve) -> Result<BigInt,FieldErrors>{
+    Ok(curve.get_prime() + 1 - trace_by_schoof(curve)?)
+}
+
+
+pub fn trace_by_schoof(curve:&EllipticCurve) -> Result<BigInt,FieldErrors>{
+    let p = curve.get_prime();
+    let bound = (p * BigInt::from(16)).sqrt();
+
+    let mut modulus = BigInt::from(2);
+    let mut trace = BigInt::from(trace_modulo(curve,2)?);
+    let mut l = 3_u64;
+    while modulus <= bound{
+        if is_probable_prime(&BigInt::from(l)) && &BigInt::from(l) != p{
+            let t = BigInt::from(trace_modulo(curve,l)?);
+            // CRT: trace + modulus k = t mod l
+            let l_big = BigInt::from(l);
+            let inverse = (&modulus % &l_big).modpow(&(&l_big - 2),&l_big);
+            let k = ((t - &trace) % &l_big + &l_big) * inverse % &l_big;
+            trace += &modulus * k;
+            modulus *= l_big;
+        }
+        l += 2;
+    }
+    if &trace * 2 > modulus{
+        trace -= modulus;
+    }
+    Ok(trace)
+}
+
+
+// t mod l for a prime l other than p
+pub fn trace_modulo(curve:&EllipticCurve, l:u64) -> Result<u64,FieldErrors>{
+    let p = curve.get_prime();
+    if !is_probable_prime(&BigInt::from(l)) || &BigInt::from(l) == p{
+        return Err(FieldErrors::InvalidParams("Trace is computed modulo primes other than the characteristic".to_string()));
+    }
+    let x = Polynomial::x(p);
+    let rhs = curve_polynomial(curve);
+
+    // a 2-torsion point exists iff x^3 + ax + b has a root, and then #E is even
+    if l == 2{
+        let x_p = x.pow_mod(p,&rhs);
+        let common = (&x_p - &x).gcd(&rhs);
+        return Ok(if common.degree() == Some(0) { 1 } else { 0 });
+    }
+
+    let psi = DivisionPolynomials::new(curve,l as usize).psi(l as usize)?.monic();
+    let mut torsion = TorsionRing::new(curve,psi);
+    loop{
+        match torsion.frobenius_trace(p,l){
+            Ok(t) => return Ok(t),
+            Err(Failure::Split(factor)) => {
+                let cofactor = torsion.modulus.div_rem(&factor).0;
+                let smaller = if factor.degree() <= cofactor.degree() { factor } else { cofactor };
+                torsion = TorsionRing::new(curve,smaller.monic());
+            },
+            Err(Failure::Inconsistent) => {
+                return Err(FieldErrors::InvalidParams("Frobenius did not act on the l-torsion as expected".to_string()));
+            },
+        }
+    }
+}
+
+
+enum Failure{
+    // the modulus has this nontrivial factor, continue on it
+    Split(Poly),
+    Inconsistent,
+}
+
+
+// (x, y Y) for polynomials x and Y, the form of every multiple of the generic
+// point (x, y) and of its Frobenius images
+#[derive(Clone)]
+struct TorsionPoint{
+    x: Poly,
+    y: Poly,
+}
+
+
+// F_p[x] / (m) for a factor m of psi_l, with y^2 replaced by x^3 + ax + b
+struct TorsionRing{
+    modulus: Poly,
+    rhs: Poly,
+    a: FieldElement,
+}
+
+
+impl TorsionRing{
+
+    fn new(curve:&EllipticCurve, modulus:Poly) -> Self{
+        let rhs = curve_polynomial(curve).rem(&modulus);
+        TorsionRing { modulus, rhs, a: curve.get_a().clone() }
+    }
+
+    fn mul(&self, f:&Poly, g:&Poly) -> Poly{
+        f.mul_mod(g,&self.modulus)
+    }
+
+    fn is_zero(&self, f:&Poly) -> Result<bool,Failure>{
+        let f = f.rem(&self.modulus);
+        if f.is_zero(){
+            return Ok(true);
+        }
+        let common = f.gcd(&self.modulus);
+        if common.degree() == Some(0) { Ok(false) } else { Err(Failure::Split(common)) }
+    }
+
+    fn invert(&self, f:&Poly) -> Result<Poly,Failure>{
+        let (common,s,_) = f.rem(&self.modulus).extended_gcd(&self.modulus);
+        match common.degree(){
+            Some(0) => Ok(s.rem(&self.modulus)),
+            Some(d) if Some(d) < self.modulus.degree() => Err(Failure::Split(common)),
+            _ => Err(Failure::Inconsistent),
+        }
+    }
+
+    // slope y L with L = (Y2 - Y1) / (X2 - X1), then X3 = F L^2 - X1 - X2
+    fn add(&self, p1:&TorsionPoint, p2:&TorsionPoint) -> Result<TorsionPoint,Failure>{
+        let slope = self.mul(&(&p2.y - &p1.y),&self.invert(&(&p2.x - &p1.x))?);
+        Ok(self.with_slope(p1,&p2.x,slope))
+    }
+
+    // slope (3 X^2 + a) / 2 y Y = y (3 X^2 + a) / 2 F Y
+    fn double(&self, p1:&TorsionPoint) -> Result<TorsionPoint,Failure>{
+        let prime = self.a.get_prime().clone();
+        let three_x2 = self.mul(&p1.x,&p1.x).scale(&FieldElement::new(BigInt::from(3) % &prime,prime.clone()).unwrap());
+        let numerator = &three_x2 + &Polynomial::constant(self.a.clone());
+        let denominator = self.mul(&self.rhs,&p1.y).scale(&FieldElement::new(BigInt::from(2) % &prime,prime).unwrap());
+        let slope = self.mul(&numerator,&self.invert(&denominator)?);
+        Ok(self.with_slope(p1,&p1.x,slope))
+    }
+
+    fn with_slope(&self, p1:&TorsionPoint, x2:&Poly, slope:Poly) -> TorsionPoint{
+        let x3 = &(&self.mul(&self.rhs,&self.mul(&slope,&slope)) - &p1.x) - x2;
+        let y3 = &self.mul(&slope,&(&p1.x - &x3)) - &p1.y;
+        TorsionPoint { x: x3.rem(&self.modulus), y: y3.rem(&self.modulus) }
+    }
+
+    // [k] point for 0 < k < l, where no intermediate sum meets a doubling case
+    fn multiply(&self, point:&TorsionPoint, k:u64) -> Result<TorsionPoint,Failure>{
+        let mut r = point.clone();
+        for i in (0..63 - k.leading_zeros()).rev(){
+            r = self.double(&r)?;
+            if (k >> i) & 1 == 1{
+                r = self.add(&r,point)?;
+            }
+        }
+        Ok(r)
+    }
+
+    // t mod l from phi^2(P) + [p] P = [t] phi(P) for the generic point P of E[l]
+    fn frobenius_trace(&self, p:&BigInt, l:u64) -> Result<u64,Failure>{
+        let prime = self.a.get_prime();
+        let x = Polynomial::x(prime);
+        let one = Polynomial::constant(FieldElement::one(prime.clone()));
+        let q = (p % l).to_u64().unwrap();
+
+        // y^p = y (x^3 + ax + b)^((p - 1) / 2)
+        let y_power = self.rhs.pow_mod(&((p - 1) / 2),&self.modulus);
+        let frobenius = TorsionPoint { x: x.pow_mod(p,&self.modulus), y: y_power };
+        let frobenius_squared = TorsionPoint {
+            x: frobenius.x.pow_mod(p,&self.modulus),
+            y: self.mul(&frobenius.y,&frobenius.y.pow_mod(p,&self.modulus)),
+        };
+        let generic = TorsionPoint { x, y: one };
+        let q_point = self.multiply(&generic,q)?;
+
+        if self.is_zero(&(&frobenius_squared.x - &q_point.x))?{
+            // phi^2 = -[p] gives t = 0
+            if self.is_zero(&(&frobenius_squared.y + &q_point.y))?{
+                return Ok(0);
+            }
+            // phi^2 = [p] makes phi act as [w] or [-w] with w^2 = p and t = 2w or -2w
+            let w = (1..l).find(|w| w * w % l == q).ok_or(Failure::Inconsistent)?;
+            let w_point = self.multiply(&generic,w)?;
+            if !self.is_zero(&(&frobenius.x - &w_point.x))?{
+                return Err(Failure::Inconsistent);
+            }
+            let t = 2 * w % l;
+            return Ok(if self.is_zero(&(&frobenius.y - &w_point.y))? { t } else { l - t });
+        }
+
+        let sum = self.add(&frobenius_squared,&q_point)?;
+        let mut multiple = frobenius.clone();
+        for tau in 1..=(l - 1) / 2{
+            if tau == 2{
+                multiple = self.double(&frobenius)?;
+            }
+            else if tau > 2{
+                multiple = self.add(&multiple,&frobenius)?;
+            }
+            if self.is_zero(&(&sum.x - &multiple.x))?{
+                return Ok(if self.is_zero(&(&sum.y - &multiple.y))? { tau } else { l - tau });
+            }
+        }
+        Err(Failure::Inconsistent)
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::curve_from_u64;
+
+
+#[test]
+fn schoof_matches_legendre(){
+    use crate::elliptic_curve::counting::enumeration::order_by_legendre;
+
+    for (a,b,p) in [(2,3,97),(0,7,1009),(1,0,1009),(5,1,10007),(123,456,65537),(7,11,1_000_003)]{
+        let curve = curve_from_u64(a,b,p);
+        let order = order_by_legendre(&curve).unwrap();
+        assert_eq!(order_by_schoof(&curve).unwrap(),order);
+        for l in [2,3,5,7]{
+            let t = (BigInt::from(p + 1) - &order) % l;
+            assert_eq!(BigInt::from(trace_modulo(&curve,l).unwrap()),(t + l) % l);
+        }
+    }
+
+    // residues for l beyond what a field this small needs
+    let curve = curve_from_u64(7,11,1_000_003);
+    let order = order_by_legendre(&curve).unwrap();
+    for l in [11,13,17,19,23]{
+        let t = (BigInt::from(1_000_004) - &order) % l;
+        assert_eq!(BigInt::from(trace_modulo(&curve,l).unwrap()),(t + l) % l);
+    }
+    assert!(trace_modulo(&curve_from_u64(2,3,97),9).is_err());
+}
+
+
+#[test]
+fn schoof_matches_bsgs(){
+    use crate::elliptic_curve::counting::bsgs::trace_by_bsgs;
+
+    let curve = curve_from_u64(3,5,1099511627791);
+    assert_eq!(trace_by_schoof(&curve).unwrap(),trace_by_bsgs(&curve).unwrap());
+}
+
+
+#[test]
+fn schoof_residues_of_named_curves(){
+    use crate::elliptic_curve::curves::{secp256k1, secp256r1};
+
+    for curve in [secp256k1(),secp256r1()]{
+        let order = curve.get_order().unwrap() * curve.get_cofactor().unwrap();
+        let trace = curve.get_prime() + 1 - order;
+        for l in [2_u64,3,5,7,11]{
+            let expected = ((&trace % l) + l) % l;
+            assert_eq!(BigInt::from(trace_modulo(&curve,l).unwrap()),expected);
+        }
+    }
+}
+
+
+
+// The largest size that runs in reasonable time: l goes up to 31 and psi_31
+// has degree 480. About a minute in release mode.
+#[test]
+#[ignore = "slow, run with cargo test --release -- --ignored"]
+fn schoof_counts_64_bit_curve(){
+    use crate::elliptic_curve::counting::bsgs::trace_by_bsgs;
+    use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+    let p = 18446744073709551557_u64;
+    let curve = curve_from_u64(3,5,p);
+    let order = order_by_schoof(&curve).unwrap();
+    assert_eq!(&order,&(BigInt::from(p) + 1 - trace_by_bsgs(&curve).unwrap()));
+    let point = curve.points().nth(1).unwrap();
+    assert!(point.to_jacobian().multiply(&order).is_identity());
+}
@@ -1,30 +1,173 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
 use crate::elliptic_curve::field::FieldElement;
 use crate::elliptic_curve::field_traits::FieldElementTraits;
-use num_bigint::BigInt;
-use num_traits::{Zero,One};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::glv::GlvEndomorphism;
+use crate::elliptic_curve::point::ops::sum_points;
 
+use crate::elliptic_curve::point::pointTraits::PointOperations;
 
-use std::ops::{Add,Sub};
 
+// Short Weierstrass curve y^2 = x^3 + ax + b together with the optional data
+// that algorithms can take advantage of: a generator with its order and
+// cofactor, and an efficiently computable endomorphism.
+#[derive(Debug,Clone,PartialEq)]
+pub struct EllipticCurve{
+    a: FieldElement,
+    b: FieldElement,
+    generator: Option<PointAffine<FieldElement>>,
+    order: Option<BigInt>,
+    cofactor: Option<BigInt>,
+    endomorphism: Option<GlvEndomorphism>,
+}
 
 
-#[derive(Debug,Clone)]
-pub struct CurveElement{
-    element: FieldElement,
-}
+impl EllipticCurve{
+
+    pub fn new(a:FieldElement,b:FieldElement) -> Result<EllipticCurve,FieldErrors>{
+        if a.get_prime() != b.get_prime(){
+            return Err(FieldErrors::Mismatch("Curve coefficients are in different fields".to_string()));
+        }
+        // the doubling and the group law formulas divide by 2 and 3
+        if a.get_prime() <= &BigInt::from(3){
+            return Err(FieldErrors::InvalidParams("Short Weierstrass curves need characteristic above 3".to_string()));
+        }
+        let four = FieldElement::new(BigInt::from(4) % a.get_prime(),a.get_prime().clone())?;
+        let twenty_seven = FieldElement::new(BigInt::from(27) % a.get_prime(),a.get_prime().clone())?;
+        let discriminant = four * &a * &a * &a + twenty_seven * &b * &b;
+        if discriminant.get_number().is_zero(){
+            return Err(FieldErrors::InvalidParams("Curve is singular, 4a^3 + 27b^2 = 0".to_string()));
+        }
+        Ok(EllipticCurve{a,b,generator:None,order:None,cofactor:None,endomorphism:None})
+    }
+
+    pub fn with_generator(mut self, generator:PointAffine<FieldElement>, order:BigInt, cofactor:BigInt) -> Result<EllipticCurve,FieldErrors>{
+        if order <= BigInt::one() || cofactor < BigInt::one(){
+            return Err(FieldErrors::InvalidParams("Order must exceed 1 and cofactor be positive".to_string()));
+        }
+        if !self.contains(&generator) || generator.is_identity(){
+            return Err(FieldErrors::PointNotOnCurve("Generator is not a point of the curve".to_string()));
+        }
+        if !generator.to_jacobian().multiply(&order).is_identity(){
+            return Err(FieldErrors::Mismatch("Generator does not have the given order".to_string()));
+        }
+        self.generator = Some(generator);
+        self.order = Some(order);
+        self.cofactor = Some(cofactor);
+        if let Some(endomorphism) = &self.endomorphism{
+            self.check_endomorphism(endomorphism)?;
+        }
+        Ok(self)
+    }
+
+    pub fn with_endomorphism(mut self, endomorphism:GlvEndomorphism) -> Result<EllipticCurve,FieldErrors>{
+        if !self.a.get_number().is_zero(){
+            return Err(FieldErrors::InvalidParams("The (x, y) -> (beta x, y) endomorphism needs a = 0".to_string()));
+        }
+        self.check_endomorphism(&endomorphism)?;
+        self.endomorphism = Some(endomorphism);
+        Ok(self)
+    }
+
+    // GLV reduces scalars mod the order of the endomorphism, so it has to be
+    // the order of the generator and act as lambda on it. Runs from whichever
+    // of the two builders is called second.
+    fn check_endomorphism(&self, endomorphism:&GlvEndomorphism) -> Result<(),FieldErrors>{
+        if endomorphism.get_beta().get_prime() != self.get_prime(){
+            return Err(FieldErrors::Mismatch("Endomorphism is over a different field".to_string()));
+        }
+        if self.order.as_ref().is_some_and(|order| order != endomorphism.get_order()){
+            return Err(FieldErrors::Mismatch("Endomorphism order differs from the generator order".to_string()));
+        }
+        if let Some(generator) = &self.generator{
+            if endomorphism.apply(generator) != generator.multiply(endomorphism.get_lambda()){
+                return Err(FieldErrors::Mismatch("Endomorphism does not act as lambda on the generator".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_a(&self) -> &FieldElement{
+        &self.a
+    }
+
+    pub fn get_b(&self) -> &FieldElement{
+        &self.b
+    }
+
+    pub fn get_prime(&self) -> &BigInt{
+        self.a.get_prime()
+    }
+
+    pub fn get_generator(&self) -> Option<&PointAffine<FieldElement>>{
+        self.generator.as_ref()
+    }
+
+    pub fn get_order(&self) -> Option<&BigInt>{
+        self.order.as_ref()
+    }
+
+    pub fn get_cofactor(&self) -> Option<&BigInt>{
+        self.cofactor.as_ref()
+    }
 
+    pub fn get_endomorphism(&self) -> Option<&GlvEndomorphism>{
+        self.endomorphism.as_ref()
+    }
 
-impl CurveElement{
+    pub fn identity(&self) -> PointAffine<FieldElement>{
+        PointAffine::identity(self.a.clone(),self.b.clone())
+    }
 
-    pub fn check_in_curve(&self){
+    pub fn sum_points(&self, points:impl IntoIterator<Item = PointAffine<FieldElement>>) -> PointAffine<FieldElement>{
+        sum_points(self.identity(),points)
+    }
+
+    pub fn contains(&self, point:&PointAffine<FieldElement>) -> bool{
+        point.get_a() == &self.a && point.get_b() == &self.b && point.is_on_curve()
+    }
 
+    pub fn point(&self, x:BigInt, y:BigInt) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let x = FieldElement::new(x,self.get_prime().clone())?;
+        let y = FieldElement::new(y,self.get_prime().clone())?;
+        let point = PointAffine::new(x,y,self.a.clone(),self.b.clone());
+        if !point.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Point does not satisfy the curve equation".to_string()));
+        }
+        Ok(point)
     }
 
-    pub fn double(&self) //-> Self
-    {
-        
-    
+    // Uses GLV decomposition when the curve declares an endomorphism and the
+    // point is known to lie in <G>, where phi acts as lambda: with cofactor 1
+    // or for G itself. Plain double-and-add otherwise. Variable time, for
+    // public scalars.
+    pub fn multiply(&self, point:&PointAffine<FieldElement>, k:&BigInt) -> PointAffine<FieldElement>{
+        match &self.endomorphism{
+            Some(endomorphism) if self.cofactor.as_ref().is_some_and(|h| h.is_one()) || self.generator.as_ref() == Some(point) =>
+                endomorphism.multiply_in_subgroup(point,k),
+            _ => point.multiply(k),
+        }
     }
 
-    
-}
\ No newline at end of file
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn curve_parameters(){
+    // 4a^3 + 27b^2 = 216 = 7 mod 11, and 0 for a = b = 0
+    assert!(EllipticCurve::new(field(3,11),field(2,11)).is_ok());
+    assert!(EllipticCurve::new(field(0,11),field(0,11)).is_err());
+    assert!(EllipticCurve::new(field(1,11),field(1,13)).is_err());
+
+    // the short Weierstrass formulas divide by 2 and 3
+    assert!(EllipticCurve::new(field(1,2),field(1,2)).is_err());
+    assert!(EllipticCurve::new(field(1,3),field(1,3)).is_err());
+}
@@ -0,0 +1,283 @@
+use num_bigint::BigInt;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::glv::GlvEndomorphism;
+use crate::elliptic_curve::number_theory::hex;
+
+
+// Domain parameters of a standard short Weierstrass curve, as published in
+// SEC 2, FIPS 186-4 and RFC 5639. All numbers are hexadecimal.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub struct CurveParameters{
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub oid: &'static str,
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub gx: &'static str,
+    pub gy: &'static str,
+    pub n: &'static str,
+    pub h: &'static str,
+}
+
+
+pub const SECP192K1: CurveParameters = CurveParameters{
+    name: "secp192k1",
+    aliases: &[],
+    oid: "1.3.132.0.31",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFEE37",
+    a: "0",
+    b: "3",
+    gx: "DB4FF10EC057E9AE26B07D0280B7F4341DA5D1B1EAE06C7D",
+    gy: "9B2F2F6D9C5628A7844163D015BE86344082AA88D95E2F9D",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFE26F2FC170F69466A74DEFD8D",
+    h: "1",
+};
+
+pub const SECP192R1: CurveParameters = CurveParameters{
+    name: "secp192r1",
+    aliases: &["P-192","prime192v1"],
+    oid: "1.2.840.10045.3.1.1",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFF",
+    a: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFC",
+    b: "64210519E59C80E70FA7E9AB72243049FEB8DEECC146B9B1",
+    gx: "188DA80EB03090F67CBF20EB43A18800F4FF0AFD82FF1012",
+    gy: "07192B95FFC8DA78631011ED6B24CDD573F977A11E794811",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFF99DEF836146BC9B1B4D22831",
+    h: "1",
+};
+
+pub const SECP224K1: CurveParameters = CurveParameters{
+    name: "secp224k1",
+    aliases: &[],
+    oid: "1.3.132.0.32",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFE56D",
+    a: "0",
+    b: "5",
+    gx: "A1455B334DF099DF30FC28A169A467E9E47075A90F7E650EB6B7A45C",
+    gy: "7E089FED7FBA344282CAFBD6F7E319F7C0B0BD59E2CA4BDB556D61A5",
+    n: "010000000000000000000000000001DCE8D2EC6184CAF0A971769FB1F7",
+    h: "1",
+};
+
+pub const SECP224R1: CurveParameters = CurveParameters{
+    name: "secp224r1",
+    aliases: &["P-224"],
+    oid: "1.3.132.0.33",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF000000000000000000000001",
+    a: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFE",
+    b: "B4050A850C04B3ABF54132565044B0B7D7BFD8BA270B39432355FFB4",
+    gx: "B70E0CBD6BB4BF7F321390B94A03C1D356C21122343280D6115C1D21",
+    gy: "BD376388B5F723FB4C22DFE6CD4375A05A07476444D5819985007E34",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFFFFFF16A2E0B8F03E13DD29455C5C2A3D",
+    h: "1",
+};
+
+pub const SECP256K1: CurveParameters = CurveParameters{
+    name: "secp256k1",
+    aliases: &[],
+    oid: "1.3.132.0.10",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F",
+    a: "0",
+    b: "7",
+    gx: "79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",
+    gy: "483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+    h: "1",
+};
+
+pub const SECP256R1: CurveParameters = CurveParameters{
+    name: "secp256r1",
+    aliases: &["P-256","prime256v1"],
+    oid: "1.2.840.10045.3.1.7",
+    p: "FFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF",
+    a: "FFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFC",
+    b: "5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B",
+    gx: "6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296",
+    gy: "4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5",
+    n: "FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+    h: "1",
+};
+
+pub const SECP384R1: CurveParameters = CurveParameters{
+    name: "secp384r1",
+    aliases: &["P-384"],
+    oid: "1.3.132.0.34",
+    p: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFF",
+    a: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFC",
+    b: "B3312FA7E23EE7E4988E056BE3F82D19181D9C6EFE8141120314088F5013875AC656398D8A2ED19D2A85C8EDD3EC2AEF",
+    gx: "AA87CA22BE8B05378EB1C71EF320AD746E1D3B628BA79B9859F741E082542A385502F25DBF55296C3A545E3872760AB7",
+    gy: "3617DE4A96262C6F5D9E98BF9292DC29F8F41DBD289A147CE9DA3113B5F0B8C00A60B1CE1D7E819D7A431D7C90EA0E5F",
+    n: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC7634D81F4372DDF581A0DB248B0A77AECEC196ACCC52973",
+    h: "1",
+};
+
+pub const SECP521R1: CurveParameters = CurveParameters{
+    name: "secp521r1",
+    aliases: &["P-521"],
+    oid: "1.3.132.0.35",
+    p: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+    a: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC",
+    b: "0051953EB9618E1C9A1F929A21A0B68540EEA2DA725B99B315F3B8B489918EF109E156193951EC7E937B1652C0BD3BB1BF073573DF883D2C34F1EF451FD46B503F00",
+    gx: "00C6858E06B70404E9CD9E3ECB662395B4429C648139053FB521F828AF606B4D3DBAA14B5E77EFE75928FE1DC127A2FFA8DE3348B3C1856A429BF97E7E31C2E5BD66",
+    gy: "011839296A789A3BC0045C8A5FB42C7D1BD998F54449579B446817AFBD17273E662C97EE72995EF42640C550B9013FAD0761353C7086A272C24088BE94769FD16650",
+    n: "01FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFA51868783BF2F966B7FCC0148F709A5D03BB5C9B8899C47AEBB6FB71E91386409",
+    h: "1",
+};
+
+pub const BRAINPOOL_P256R1: CurveParameters = CurveParameters{
+    name: "brainpoolP256r1",
+    aliases: &[],
+    oid: "1.3.36.3.3.2.8.1.1.7",
+    p: "A9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5377",
+    a: "7D5A0975FC2C3057EEF67530417AFFE7FB8055C126DC5C6CE94A4B44F330B5D9",
+    b: "26DC5C6CE94A4B44F330B5D9BBD77CBF958416295CF7E1CE6BCCDC18FF8C07B6",
+    gx: "8BD2AEB9CB7E57CB2C4B482FFC81B7AFB9DE27E1E3BD23C23A4453BD9ACE3262",
+    gy: "547EF835C3DAC4FD97F8461A14611DC9C27745132DED8E545C1D54C72F046997",
+    n: "A9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7",
+    h: "1",
+};
+
+pub const BRAINPOOL_P384R1: CurveParameters = CurveParameters{
+    name: "brainpoolP384r1",
+    aliases: &[],
+    oid: "1.3.36.3.3.2.8.1.1.11",
+    p: "8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B412B1DA197FB71123ACD3A729901D1A71874700133107EC53",
+    a: "7BC382C63D8C150C3C72080ACE05AFA0C2BEA28E4FB22787139165EFBA91F90F8AA5814A503AD4EB04A8C7DD22CE2826",
+    b: "04A8C7DD22CE28268B39B55416F0447C2FB77DE107DCD2A62E880EA53EEB62D57CB4390295DBC9943AB78696FA504C11",
+    gx: "1D1C64F068CF45FFA2A63A81B7C13F6B8847A3E77EF14FE3DB7FCAFE0CBD10E8E826E03436D646AAEF87B2E247D4AF1E",
+    gy: "8ABE1D7520F9C2A45CB1EB8E95CFD55262B70B29FEEC5864E19C054FF99129280E4646217791811142820341263C5315",
+    n: "8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B31F166E6CAC0425A7CF3AB6AF6B7FC3103B883202E9046565",
+    h: "1",
+};
+
+
+pub const CURVES: &[CurveParameters] = &[
+    SECP192K1,
+    SECP192R1,
+    SECP224K1,
+    SECP224R1,
+    SECP256K1,
+    SECP256R1,
+    SECP384R1,
+    SECP521R1,
+    BRAINPOOL_P256R1,
+    BRAINPOOL_P384R1,
+];
+
+
+impl CurveParameters{
+
+    pub fn get_prime(&self) -> BigInt{
+        hex(self.p)
+    }
+
+    pub fn get_order(&self) -> BigInt{
+        hex(self.n)
+    }
+
+    pub fn get_cofactor(&self) -> BigInt{
+        hex(self.h)
+    }
+
+    pub fn matches(&self, name:&str) -> bool{
+        self.name.eq_ignore_ascii_case(name) || self.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name))
+    }
+
+    // Curves with a = 0 and p = 1 mod 3 also get their GLV endomorphism.
+    pub fn to_curve(&self) -> EllipticCurve{
+        let p = self.get_prime();
+        let a = FieldElement::new(hex(self.a),p.clone()).unwrap();
+        let b = FieldElement::new(hex(self.b),p).unwrap();
+        let curve = EllipticCurve::new(a,b).unwrap();
+        let generator = curve.point(hex(self.gx),hex(self.gy)).unwrap();
+        let order = self.get_order();
+        let curve = curve.with_generator(generator.clone(),order.clone(),self.get_cofactor()).unwrap();
+
+        if self.a != "0"{
+            return curve;
+        }
+        match GlvEndomorphism::find(&generator,&order){
+            Ok(endomorphism) => curve.clone().with_endomorphism(endomorphism).unwrap_or(curve),
+            Err(_) => curve,
+        }
+    }
+
+}
+
+
+pub fn parameters_by_name(name:&str) -> Option<&'static CurveParameters>{
+    CURVES.iter().find(|c| c.matches(name))
+}
+
+
+pub fn parameters_by_oid(oid:&str) -> Option<&'static CurveParameters>{
+    CURVES.iter().find(|c| c.oid == oid)
+}
+
+
+pub fn by_name(name:&str) -> Option<EllipticCurve>{
+    parameters_by_name(name).map(|c| c.to_curve())
+}
+
+
+pub fn by_oid(oid:&str) -> Option<EllipticCurve>{
+    parameters_by_oid(oid).map(|c| c.to_curve())
+}
+
+
+pub fn secp256k1() -> EllipticCurve{
+    SECP256K1.to_curve()
+}
+
+pub fn secp256r1() -> EllipticCurve{
+    SECP256R1.to_curve()
+}
+
+pub fn p384() -> EllipticCurve{
+    SECP384R1.to_curve()
+}
+
+pub fn p521() -> EllipticCurve{
+    SECP521R1.to_curve()
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[test]
+fn named_curves_are_consistent(){
+    for params in CURVES{
+        let curve = params.to_curve();
+        let generator = curve.get_generator().unwrap();
+        assert!(generator.is_on_curve(),"{}",params.name);
+
+        let n = params.get_order();
+        assert!(generator.to_jacobian().multiply(&n).is_identity(),"{}",params.name);
+        assert!(!generator.to_jacobian().multiply(&(&n - 1)).is_identity(),"{}",params.name);
+
+        // Hasse: |p + 1 - hn| <= 2 sqrt(p)
+        let trace: BigInt = params.get_prime() + 1 - params.get_cofactor() * &n;
+        assert!(&trace * &trace <= params.get_prime() * 4,"{}",params.name);
+    }
+}
+
+
+#[test]
+fn named_curve_lookup(){
+    assert_eq!(parameters_by_name("P-256"),Some(&SECP256R1));
+    assert_eq!(parameters_by_name("prime256v1"),Some(&SECP256R1));
+    assert_eq!(parameters_by_name("SECP256K1"),Some(&SECP256K1));
+    assert_eq!(parameters_by_oid("1.3.132.0.35"),Some(&SECP521R1));
+    assert_eq!(parameters_by_oid("1.3.36.3.3.2.8.1.1.7"),Some(&BRAINPOOL_P256R1));
+    assert!(by_name("curve9999").is_none());
+
+    assert_eq!(by_oid("1.3.132.0.10"),Some(secp256k1()));
+    assert!(secp256k1().get_endomorphism().is_some());
+    assert!(secp256r1().get_endomorphism().is_none());
+}
@@ -0,0 +1,482 @@
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::Field;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::ops::sum_points;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+use crate::elliptic_curve::number_theory::hex;
+
+
+// Twisted Edwards curve a x^2 + y^2 = 1 + d x^2 y^2, nonsingular when
+// a d (a - d) != 0. The addition law is complete, with no exceptional inputs,
+// when a is a square and d is not.
+#[derive(Debug,Clone,PartialEq)]
+pub struct EdwardsCurve<F>{
+    a: F,
+    d: F,
+    generator: Option<EdwardsPoint<F>>,
+    order: Option<BigInt>,
+    cofactor: Option<BigInt>,
+}
+
+
+// The neutral element (0, 1) is an ordinary affine point, no sentinel needed.
+#[derive(Debug,Clone,PartialEq)]
+pub struct EdwardsPoint<F>{
+    values: [F;2],
+    curve: [F;2],
+}
+
+
+// Extended coordinates of Hisil, Wong, Carter and Dawson: (X:Y:Z:T) is the
+// affine point (X/Z, Y/Z) with T = XY/Z.
+#[derive(Debug,Clone,PartialEq)]
+pub struct EdwardsExtended<F>{
+    values: [F;4],
+    curve: [F;2],
+}
+
+
+impl<F: Field> EdwardsCurve<F>{
+
+    pub fn new(a:F,d:F) -> Result<EdwardsCurve<F>,FieldErrors>{
+        if a.get_prime() != d.get_prime(){
+            return Err(FieldErrors::Mismatch("Curve coefficients are in different fields".to_string()));
+        }
+        if a.is_zero() || d.is_zero() || a == d{
+            return Err(FieldErrors::InvalidParams("Curve is singular, a d (a - d) = 0".to_string()));
+        }
+        Ok(EdwardsCurve{a,d,generator:None,order:None,cofactor:None})
+    }
+
+    pub fn with_generator(mut self, generator:EdwardsPoint<F>, order:BigInt, cofactor:BigInt) -> Result<EdwardsCurve<F>,FieldErrors>{
+        if !self.contains(&generator) || generator.is_identity(){
+            return Err(FieldErrors::PointNotOnCurve("Generator is not a point of the curve".to_string()));
+        }
+        if !generator.multiply(&order).is_identity(){
+            return Err(FieldErrors::Mismatch("Generator does not have the given order".to_string()));
+        }
+        self.generator = Some(generator);
+        self.order = Some(order);
+        self.cofactor = Some(cofactor);
+        Ok(self)
+    }
+
+    pub fn get_a(&self) -> &F{
+        &self.a
+    }
+
+    pub fn get_d(&self) -> &F{
+        &self.d
+    }
+
+    pub fn get_generator(&self) -> Option<&EdwardsPoint<F>>{
+        self.generator.as_ref()
+    }
+
+    pub fn get_order(&self) -> Option<&BigInt>{
+        self.order.as_ref()
+    }
+
+    pub fn get_cofactor(&self) -> Option<&BigInt>{
+        self.cofactor.as_ref()
+    }
+
+    pub fn is_complete(&self) -> bool{
+        self.a.is_square() && !self.d.is_square()
+    }
+
+    pub fn identity(&self) -> EdwardsPoint<F>{
+        EdwardsPoint::identity(self.a.clone(),self.d.clone())
+    }
+
+    pub fn sum_points(&self, points:impl IntoIterator<Item = EdwardsPoint<F>>) -> EdwardsPoint<F>{
+        sum_points(self.identity(),points)
+    }
+
+    pub fn contains(&self, point:&EdwardsPoint<F>) -> bool{
+        point.get_a() == &self.a && point.get_d() == &self.d && point.is_on_curve()
+    }
+
+    pub fn point(&self, x:F, y:F) -> Result<EdwardsPoint<F>,FieldErrors>{
+        let point = EdwardsPoint::new(x,y,self.a.clone(),self.d.clone());
+        if !point.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Point does not satisfy the curve equation".to_string()));
+        }
+        Ok(point)
+    }
+
+    // [h]P, maps any curve point into the subgroup of order n
+    pub fn clear_cofactor(&self, point:&EdwardsPoint<F>) -> Result<EdwardsPoint<F>,FieldErrors>{
+        let cofactor = self.cofactor.as_ref()
+            .ok_or(FieldErrors::InvalidParams("Curve has no cofactor set".to_string()))?;
+        Ok(point.to_extended().multiply(cofactor).to_affine())
+    }
+
+    // [h]P = O, the points that cofactor clearing sends to the identity
+    pub fn is_small_order(&self, point:&EdwardsPoint<F>) -> Result<bool,FieldErrors>{
+        Ok(self.clear_cofactor(point)?.is_identity())
+    }
+
+    // [n]P = O, true exactly for the points of the prime order subgroup
+    pub fn is_torsion_free(&self, point:&EdwardsPoint<F>) -> Result<bool,FieldErrors>{
+        let order = self.order.as_ref()
+            .ok_or(FieldErrors::InvalidParams("Curve has no group order set".to_string()))?;
+        Ok(point.to_extended().multiply(order).is_identity())
+    }
+
+}
+
+
+impl<F: Field> EdwardsPoint<F>{
+
+    pub fn new(x:F,y:F,a:F,d:F) -> Self{
+        EdwardsPoint{values: [x,y],curve: [a,d]}
+    }
+
+    pub fn identity(a:F,d:F) -> Self{
+        EdwardsPoint{values: [a.zero_like(),a.one_like()],curve: [a,d]}
+    }
+
+    pub fn get_x(&self) -> &F{
+        &self.values[0]
+    }
+    pub fn get_y(&self) -> &F{
+        &self.values[1]
+    }
+    pub fn get_a(&self) -> &F{
+        &self.curve[0]
+    }
+    pub fn get_d(&self) -> &F{
+        &self.curve[1]
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.get_x().is_zero() && self.get_y().is_one()
+    }
+
+    pub fn is_on_curve(&self) -> bool{
+        let x2 = self.get_x().square();
+        let y2 = self.get_y().square();
+        self.get_a().clone() * &x2 + &y2 == x2.one_like() + self.get_d().clone() * x2 * y2
+    }
+
+    pub fn negate(&self) -> Self{
+        EdwardsPoint{values: [self.get_x().negate(),self.get_y().clone()],curve: self.curve.clone()}
+    }
+
+    pub fn to_extended(&self) -> EdwardsExtended<F>{
+        let (x,y) = (self.get_x().clone(),self.get_y().clone());
+        let t = x.clone() * &y;
+        EdwardsExtended{values: [x,y,t.one_like(),t],curve: self.curve.clone()}
+    }
+
+}
+
+
+impl<F: Field> EdwardsExtended<F>{
+
+    pub fn new(x:F,y:F,z:F,t:F,a:F,d:F) -> Self{
+        EdwardsExtended{values: [x,y,z,t],curve: [a,d]}
+    }
+
+    pub fn identity(a:F,d:F) -> Self{
+        EdwardsExtended{values: [a.zero_like(),a.one_like(),a.one_like(),a.zero_like()],curve: [a,d]}
+    }
+
+    pub fn get_x(&self) -> &F{
+        &self.values[0]
+    }
+    pub fn get_y(&self) -> &F{
+        &self.values[1]
+    }
+    pub fn get_z(&self) -> &F{
+        &self.values[2]
+    }
+    pub fn get_t(&self) -> &F{
+        &self.values[3]
+    }
+    pub fn get_a(&self) -> &F{
+        &self.curve[0]
+    }
+    pub fn get_d(&self) -> &F{
+        &self.curve[1]
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.get_x().is_zero() && self.get_y() == self.get_z()
+    }
+
+    pub fn negate(&self) -> Self{
+        EdwardsExtended{
+            values: [self.get_x().negate(),self.get_y().clone(),self.get_z().clone(),self.get_t().negate()],
+            curve: self.curve.clone()
+        }
+    }
+
+    pub fn to_affine(&self) -> EdwardsPoint<F>{
+        let z_inv = self.get_z().modinv();
+        let x = self.get_x().clone() * &z_inv;
+        let y = self.get_y().clone() * z_inv;
+        EdwardsPoint{values: [x,y],curve: self.curve.clone()}
+    }
+
+}
+
+
+impl<F: Field> PointOperations for EdwardsPoint<F>{
+
+    // x3 = (x1 y2 + y1 x2) / (1 + d x1 x2 y1 y2), y3 = (y1 y2 - a x1 x2) / (1 - d x1 x2 y1 y2)
+    fn add(&self, other: Self) -> Self{
+        let (x1,y1) = (self.get_x(),self.get_y());
+        let (x2,y2) = (other.get_x(),other.get_y());
+
+        let x1x2 = x1.clone() * x2;
+        let y1y2 = y1.clone() * y2;
+        let t = self.get_d().clone() * &x1x2 * &y1y2;
+        let one = t.one_like();
+
+        let x3 = (x1.clone() * y2 + y1.clone() * x2) / (one.clone() + &t);
+        let y3 = (y1y2 - self.get_a().clone() * x1x2) / (one - t);
+        EdwardsPoint{values: [x3,y3],curve: self.curve.clone()}
+    }
+
+    fn double(&self) -> Self{
+        self.add(self.clone())
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        self.to_extended().multiply(n).to_affine()
+    }
+
+}
+
+
+impl<F: Field> PointOperations for EdwardsExtended<F>{
+
+    // add-2008-hwcd, unified: also valid for P + P and P + O, 9M + 1*a + 1*d
+    fn add(&self, other: Self) -> Self{
+        let (x1,y1,z1,t1) = (self.get_x(),self.get_y(),self.get_z(),self.get_t());
+        let (x2,y2,z2,t2) = (other.get_x(),other.get_y(),other.get_z(),other.get_t());
+
+        let a = x1.clone() * x2;
+        let b = y1.clone() * y2;
+        let c = t1.clone() * self.get_d() * t2;
+        let d = z1.clone() * z2;
+        let e = (x1.clone() + y1) * (x2.clone() + y2) - &a - &b;
+        let f = d.clone() - &c;
+        let g = d + c;
+        let h = b - self.get_a().clone() * a;
+
+        let x3 = e.clone() * &f;
+        let y3 = g.clone() * &h;
+        let t3 = e * h;
+        let z3 = f * g;
+        EdwardsExtended{values: [x3,y3,z3,t3],curve: self.curve.clone()}
+    }
+
+    // dbl-2008-hwcd, 4M + 4S + 1*a
+    fn double(&self) -> Self{
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+
+        let a = x1.square();
+        let b = y1.square();
+        let c = z1.square() * z1.integer_like(2);
+        let d = self.get_a().clone() * &a;
+        let e = (x1.clone() + y1).square() - &a - &b;
+        let g = d.clone() + &b;
+        let f = g.clone() - c;
+        let h = d - b;
+
+        let x3 = e.clone() * &f;
+        let y3 = g.clone() * &h;
+        let t3 = e * h;
+        let z3 = f * g;
+        EdwardsExtended{values: [x3,y3,z3,t3],curve: self.curve.clone()}
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
+        }
+        let mut q = self.clone();
+        let mut r = Self::identity(self.get_a().clone(),self.get_d().clone());
+
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r = r.add(q.clone());
+            }
+            q = q.double();
+        }
+        r
+    }
+
+}
+
+
+fn edwards_curve(p:&str, a:&str, d:&str, gx:&str, gy:&str, n:&str, h:u32) -> EdwardsCurve<FieldElement>{
+    let p = hex(p);
+    let element = |s:&str| FieldElement::new(hex(s),p.clone()).unwrap();
+    let (a,d) = (element(a),element(d));
+    let generator = EdwardsPoint::new(element(gx),element(gy),a.clone(),d.clone());
+
+    EdwardsCurve::new(a,d).unwrap()
+        .with_generator(generator,hex(n),BigInt::from(h)).unwrap()
+}
+
+
+// RFC 8032 section 5.1, -x^2 + y^2 = 1 - (121665/121666) x^2 y^2 over 2^255 - 19
+pub fn ed25519() -> EdwardsCurve<FieldElement>{
+    edwards_curve(
+        "7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFED",
+        "7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEC",
+        "52036CEE2B6FFE738CC740797779E89800700A4D4141D8AB75EB4DCA135978A3",
+        "216936D3CD6E53FEC0A4E231FDD6DC5C692CC7609525A7B2C9562D608F25D51A",
+        "6666666666666666666666666666666666666666666666666666666666666658",
+        "1000000000000000000000000000000014DEF9DEA2F79CD65812631A5CF5D3ED",
+        8,
+    )
+}
+
+
+// RFC 8032 section 5.2, x^2 + y^2 = 1 - 39081 x^2 y^2 over 2^448 - 2^224 - 1
+pub fn ed448() -> EdwardsCurve<FieldElement>{
+    edwards_curve(
+        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+        "1",
+        "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF6756",
+        "4F1970C66BED0DED221D15A622BF36DA9E146570470F1767EA6DE324A3D3A46412AE1AF72AB66511433B80E18B00938E2626A82BC70CC05E",
+        "693F46716EB6BC248876203756C9C7624BEA73736CA3984087789C1E05A0C2D73AD3FF1CE67C39C4FDBD132C4ED7C8AD9808795BF230FA14",
+        "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7CCA23E9C44EDB49AED63690216CC2728DC58F552378C292AB5844F3",
+        4,
+    )
+}
+
+
+// Zcash Jubjub, -x^2 + y^2 = 1 - (10240/10241) x^2 y^2 over the BLS12-381
+// scalar field, with the generator used by arkworks
+pub fn jubjub() -> EdwardsCurve<FieldElement>{
+    edwards_curve(
+        "73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001",
+        "73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000000",
+        "2A9318E74BFA2B48F5FD9207E6BD7FD4292D7F6D37579D2601065FD6D6343EB1",
+        "11DAFE5D23E1218086A365B99FBF3D3BE72F6AFD7D1F72623E6B071492D1122B",
+        "1D523CF1DDAB1A1793132E78C866C0C33E26BA5CC220FED7CC3F870E59D292AA",
+        "0E7DB4EA6533AFA906673B0101343B00A6682093CCC81082D0970E5ED6F72CB7",
+        8,
+    )
+}
+
+
+// Bandersnatch (Masson, Sanso and Zhang), a = -5 over the BLS12-381 scalar field
+pub fn bandersnatch() -> EdwardsCurve<FieldElement>{
+    edwards_curve(
+        "73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001",
+        "73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFEFFFFFFFC",
+        "6389C12633C267CBC66E3BF86BE3B6D8CB66677177E54F92B369F2F5188D58E7",
+        "29C132CC2C0B34C5743711777BBE42F32B79C022AD998465E1E71866A252AE18",
+        "2A6C669EDA123E0F157D8B50BADCD586358CAD81EEE464605E3167B6CC974166",
+        "1CFB69D4CA675F520CCE760202687600FF8F87007419047174FD06B52876E7E1",
+        4,
+    )
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[cfg(test)]
+fn all_points(curve:&EdwardsCurve<FieldElement>, p:i32) -> Vec<EdwardsPoint<FieldElement>>{
+    let mut points = vec![];
+    for x in 0..p{
+        for y in 0..p{
+            if let Ok(point) = curve.point(field(x,p),field(y,p)){
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+
+#[test]
+fn edwards_rejects_singular_curves(){
+    assert!(EdwardsCurve::new(field(0,101),field(5,101)).is_err());
+    assert!(EdwardsCurve::new(field(1,101),field(0,101)).is_err());
+    assert!(EdwardsCurve::new(field(7,101),field(7,101)).is_err());
+}
+
+
+#[test]
+fn edwards_group_law(){
+    // a = 1 is a square and 2 is not a square mod 101, so the law is complete
+    let curve = EdwardsCurve::new(field(1,101),field(2,101)).unwrap();
+    assert!(curve.is_complete());
+    let points = all_points(&curve,101);
+    let order = BigInt::from(points.len());
+
+    // (0, -1) has order two, 4 divides the order of every Edwards curve
+    assert!(points.contains(&curve.point(field(0,101),field(100,101)).unwrap()));
+    assert_eq!(points.len() % 4,0);
+
+    for p1 in points.iter(){
+        assert!(p1.multiply(&order).is_identity());
+        assert!(p1.add(p1.negate()).is_identity());
+        assert_eq!(p1.add(curve.identity()),p1.clone());
+        assert_eq!(p1.double(),p1.to_extended().double().to_affine());
+    }
+}
+
+
+#[test]
+fn extended_matches_affine(){
+    // a = 4 with d = 2 not a square mod 101, the unified formulas cover every pair of inputs
+    let curve = EdwardsCurve::new(field(4,101),field(2,101)).unwrap();
+    assert!(curve.is_complete());
+    let points = all_points(&curve,101);
+
+    for p1 in points.iter().step_by(7){
+        for p2 in points.iter().step_by(5){
+            assert_eq!(p1.to_extended().add(p2.to_extended()).to_affine(),p1.add(p2.clone()));
+        }
+        for k in -10..10{
+            let k = BigInt::from(k);
+            assert_eq!(p1.to_extended().multiply(&k).to_affine(),p1.multiply(&k));
+        }
+    }
+}
+
+
+#[test]
+fn named_edwards_curves(){
+    for curve in [ed25519(),ed448(),jubjub(),bandersnatch()]{
+        let generator = curve.get_generator().unwrap();
+        let order = curve.get_order().unwrap();
+        assert!(curve.contains(generator));
+        assert!(curve.is_torsion_free(generator).unwrap());
+        assert!(!curve.is_small_order(generator).unwrap());
+        assert!(generator.multiply(order).is_identity());
+    }
+}
+
+
+#[test]
+fn ed25519_cofactor(){
+    let curve = ed25519();
+    let generator = curve.get_generator().unwrap();
+    let p = hex("7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFED");
+
+    // (0, -1) has order two
+    let torsion = curve.point(FieldElement::zero(p.clone()),FieldElement::new(&p - 1,p.clone()).unwrap()).unwrap();
+    assert!(curve.is_small_order(&torsion).unwrap());
+    assert!(!curve.is_torsion_free(&torsion).unwrap());
+
+    let mixed = generator.add(torsion);
+    assert!(!curve.is_torsion_free(&mixed).unwrap());
+    assert_eq!(curve.clear_cofactor(&mixed).unwrap(),generator.multiply(&BigInt::from(8)));
+}
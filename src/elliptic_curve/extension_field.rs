@@ -0,0 +1,481 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+use crate::elliptic_curve::field_traits::{FieldElementTraits,Field};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::number_theory::is_probable_prime;
+
+use std::ops::{Add,Sub,Mul,Div};
+use std::fmt;
+
+
+// GF(p^k) as polynomials over GF(p) modulo a monic irreducible polynomial of
+// degree k. Coefficients are stored lowest degree first. Binary fields GF(2^m)
+// are the case p = 2; curves over them need the long Weierstrass form, the
+// short Weierstrass point types only work in characteristic above 3.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ExtensionField{
+    p: BigInt,
+    modulus: Vec<BigInt>,
+}
+
+
+// An element keeps its coefficients together with the integer sum c_i p^i of
+// them, which is what `get_number` hands out (0x53 in GF(2^8) is x^6 + x^4 + x + 1).
+#[derive(Debug,Clone,PartialEq)]
+pub struct ExtensionFieldElement{
+    n: BigInt,
+    coeffs: Vec<BigInt>,
+    field: ExtensionField,
+}
+
+
+impl ExtensionField{
+
+    pub fn new(p:BigInt, modulus:Vec<BigInt>) -> Result<ExtensionField,FieldErrors>{
+        if !is_probable_prime(&p){
+            return Err(FieldErrors::InvalidParams("Characteristic must be a prime".to_string()));
+        }
+        let modulus = reduce_coeffs(&modulus,&p);
+        if modulus.len() < 2 || !modulus.last().unwrap().is_one(){
+            return Err(FieldErrors::InvalidParams("Modulus must be monic of degree at least one".to_string()));
+        }
+        if !is_irreducible(&modulus,&p){
+            return Err(FieldErrors::InvalidParams("Modulus is not irreducible".to_string()));
+        }
+        Ok(ExtensionField { p, modulus })
+    }
+
+    // GF(p^k) with the first monic irreducible modulus of degree k, ordering
+    // the lower coefficients by the integer they encode
+    pub fn with_degree(p:BigInt, k:usize) -> Result<ExtensionField,FieldErrors>{
+        if !is_probable_prime(&p) || k == 0{
+            return Err(FieldErrors::InvalidParams("Need a prime and a degree of at least one".to_string()));
+        }
+        let mut n = BigInt::zero();
+        loop{
+            let mut modulus = Vec::with_capacity(k + 1);
+            let mut rest = n.clone();
+            for _ in 0..k{
+                modulus.push(&rest % &p);
+                rest /= &p;
+            }
+            modulus.push(BigInt::one());
+            if is_irreducible(&modulus,&p){
+                return Ok(ExtensionField { p, modulus });
+            }
+            n += 1;
+        }
+    }
+
+    pub fn get_prime(&self) -> &BigInt{
+        &self.p
+    }
+
+    pub fn get_modulus(&self) -> &Vec<BigInt>{
+        &self.modulus
+    }
+
+    pub fn degree(&self) -> usize{
+        self.modulus.len() - 1
+    }
+
+    pub fn order(&self) -> BigInt{
+        self.p.pow(self.degree() as u32)
+    }
+
+    pub fn element(&self, coeffs:Vec<BigInt>) -> ExtensionFieldElement{
+        let coeffs = poly_rem(&reduce_coeffs(&coeffs,&self.p),&self.modulus,&self.p);
+        ExtensionFieldElement { n: encode(&coeffs,&self.p), coeffs, field: self.clone() }
+    }
+
+    // inverse of `get_number`, n must be in [0, p^k)
+    pub fn from_number(&self, n:BigInt) -> Result<ExtensionFieldElement,FieldErrors>{
+        if n.is_negative() || n >= self.order(){
+            return Err(FieldErrors::InvalidParams("Bad Parameters".to_string()));
+        }
+        let mut coeffs = Vec::new();
+        let mut rest = n;
+        while !rest.is_zero(){
+            coeffs.push(&rest % &self.p);
+            rest /= &self.p;
+        }
+        Ok(self.element(coeffs))
+    }
+
+    pub fn zero(&self) -> ExtensionFieldElement{
+        self.element(vec![])
+    }
+
+    pub fn one(&self) -> ExtensionFieldElement{
+        self.element(vec![BigInt::one()])
+    }
+
+}
+
+
+impl ExtensionFieldElement{
+
+    pub fn get_coefficients(&self) -> &Vec<BigInt>{
+        &self.coeffs
+    }
+
+    pub fn get_field(&self) -> &ExtensionField{
+        &self.field
+    }
+
+    fn with_coeffs(&self, coeffs:Vec<BigInt>) -> ExtensionFieldElement{
+        ExtensionFieldElement { n: encode(&coeffs,&self.field.p), coeffs, field: self.field.clone() }
+    }
+
+    fn add_ref(&self, other:&ExtensionFieldElement) -> ExtensionFieldElement{
+        self.with_coeffs(poly_add(&self.coeffs,&other.coeffs,&self.field.p))
+    }
+
+    fn sub_ref(&self, other:&ExtensionFieldElement) -> ExtensionFieldElement{
+        self.with_coeffs(poly_sub(&self.coeffs,&other.coeffs,&self.field.p))
+    }
+
+    fn mul_ref(&self, other:&ExtensionFieldElement) -> ExtensionFieldElement{
+        let product = poly_mul(&self.coeffs,&other.coeffs,&self.field.p);
+        self.with_coeffs(poly_rem(&product,&self.field.modulus,&self.field.p))
+    }
+
+}
+
+
+impl FieldElementTraits for ExtensionFieldElement{
+
+    fn get_number(&self) -> &BigInt{
+        &self.n
+    }
+
+    // the characteristic, as for prime fields
+    fn get_prime(&self) -> &BigInt{
+        &self.field.p
+    }
+
+    // a^(q-2), the multiplicative group has order q - 1
+    fn modinv(&self) -> ExtensionFieldElement{
+        self.pow(&(self.field.order() - BigInt::from(2)))
+    }
+
+}
+
+
+impl Field for ExtensionFieldElement{
+
+    fn zero_like(&self) -> ExtensionFieldElement{
+        self.field.zero()
+    }
+
+    fn one_like(&self) -> ExtensionFieldElement{
+        self.field.one()
+    }
+
+    fn integer_like(&self, n:i64) -> ExtensionFieldElement{
+        self.field.element(vec![BigInt::from(n)])
+    }
+
+    fn is_zero(&self) -> bool{
+        self.coeffs.is_empty()
+    }
+
+    fn order(&self) -> BigInt{
+        self.field.order()
+    }
+
+    // the prime subfield is made of squares once k is even, so walk the whole field
+    fn non_residue(&self) -> Option<ExtensionFieldElement>{
+        let order = self.field.order();
+        let mut n = BigInt::from(2);
+        while n < order{
+            let z = self.field.from_number(n.clone()).unwrap();
+            if !z.is_square(){
+                return Some(z);
+            }
+            n += 1;
+        }
+        None
+    }
+
+}
+
+
+impl Add<ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn add(self, other:ExtensionFieldElement) -> Self::Output{
+        self.add_ref(&other)
+    }
+}
+
+
+impl Add<&ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn add(self, other:&ExtensionFieldElement) -> Self::Output{
+        self.add_ref(other)
+    }
+}
+
+
+impl Sub<ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn sub(self, other:ExtensionFieldElement) -> Self::Output{
+        self.sub_ref(&other)
+    }
+}
+
+
+impl Sub<&ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn sub(self, other:&ExtensionFieldElement) -> Self::Output{
+        self.sub_ref(other)
+    }
+}
+
+
+impl Mul<ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn mul(self, other:ExtensionFieldElement) -> Self::Output{
+        self.mul_ref(&other)
+    }
+}
+
+
+impl Mul<&ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn mul(self, other:&ExtensionFieldElement) -> Self::Output{
+        self.mul_ref(other)
+    }
+}
+
+
+impl Div<ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn div(self, other:ExtensionFieldElement) -> Self::Output{
+        self.mul_ref(&other.modinv())
+    }
+}
+
+
+impl Div<&ExtensionFieldElement> for ExtensionFieldElement{
+    type Output = ExtensionFieldElement;
+
+    fn div(self, other:&ExtensionFieldElement) -> Self::Output{
+        self.mul_ref(&other.modinv())
+    }
+}
+
+
+impl fmt::Display for ExtensionFieldElement{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f,"Number : {} , Field : GF({}^{})",self.n,self.field.p,self.field.degree())
+
+    }
+}
+
+
+// polynomial arithmetic over GF(p), all results have no leading zero coefficients
+
+fn trim(mut a:Vec<BigInt>) -> Vec<BigInt>{
+    while a.last().is_some_and(|c| c.is_zero()){
+        a.pop();
+    }
+    a
+}
+
+fn reduce(c:&BigInt, p:&BigInt) -> BigInt{
+    let r = c % p;
+    if r.is_negative(){ r + p } else { r }
+}
+
+fn reduce_coeffs(a:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    trim(a.iter().map(|c| reduce(c,p)).collect())
+}
+
+fn encode(a:&[BigInt], p:&BigInt) -> BigInt{
+    a.iter().rev().fold(BigInt::zero(),|acc,c| acc * p + c)
+}
+
+fn poly_add(a:&[BigInt], b:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    let zero = BigInt::zero();
+    let sum = (0..a.len().max(b.len()))
+        .map(|i| (a.get(i).unwrap_or(&zero) + b.get(i).unwrap_or(&zero)) % p)
+        .collect();
+    trim(sum)
+}
+
+fn poly_sub(a:&[BigInt], b:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    let zero = BigInt::zero();
+    let diff = (0..a.len().max(b.len()))
+        .map(|i| reduce(&(a.get(i).unwrap_or(&zero) - b.get(i).unwrap_or(&zero)),p))
+        .collect();
+    trim(diff)
+}
+
+fn poly_mul(a:&[BigInt], b:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    if a.is_empty() || b.is_empty(){
+        return vec![];
+    }
+    let mut product = vec![BigInt::zero(); a.len() + b.len() - 1];
+    for (i,x) in a.iter().enumerate(){
+        for (j,y) in b.iter().enumerate(){
+            product[i + j] = (&product[i + j] + x * y) % p;
+        }
+    }
+    trim(product)
+}
+
+// remainder of a divided by a nonzero m
+fn poly_rem(a:&[BigInt], m:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    let mut r = a.to_vec();
+    let lead_inv = m.last().unwrap().modpow(&(p - BigInt::from(2)),p);
+    while r.len() >= m.len(){
+        let shift = r.len() - m.len();
+        let factor = (r.last().unwrap() * &lead_inv) % p;
+        for (i,c) in m.iter().enumerate(){
+            r[shift + i] = reduce(&(&r[shift + i] - &factor * c),p);
+        }
+        r = trim(r);
+    }
+    r
+}
+
+fn poly_gcd(a:&[BigInt], b:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    let (mut a,mut b) = (a.to_vec(),b.to_vec());
+    while !b.is_empty(){
+        let r = poly_rem(&a,&b,p);
+        a = b;
+        b = r;
+    }
+    a
+}
+
+// Ben-Or: f of degree k is irreducible iff gcd(x^(p^i) - x, f) = 1 for i <= k/2
+fn is_irreducible(f:&[BigInt], p:&BigInt) -> bool{
+    let x = vec![BigInt::zero(),BigInt::one()];
+    let mut h = poly_rem(&x,f,p);
+    for _ in 0..(f.len() - 1) / 2{
+        h = poly_powmod(&h,p,f,p);
+        if poly_gcd(f,&poly_sub(&h,&x,p),p).len() != 1{
+            return false;
+        }
+    }
+    true
+}
+
+fn poly_powmod(a:&[BigInt], e:&BigInt, m:&[BigInt], p:&BigInt) -> Vec<BigInt>{
+    let mut result = vec![BigInt::one()];
+    for i in (0..e.bits()).rev(){
+        result = poly_rem(&poly_mul(&result,&result,p),m,p);
+        if e.bit(i){
+            result = poly_rem(&poly_mul(&result,a,p),m,p);
+        }
+    }
+    result
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::point::PointAffine;
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[cfg(test)]
+fn gf49() -> ExtensionField{
+    // t^2 + 1 is irreducible over GF(7) since 7 = 3 mod 4
+    ExtensionField::new(BigInt::from(7),vec![BigInt::one(),BigInt::zero(),BigInt::one()]).unwrap()
+}
+
+
+#[test]
+fn irreducibility_check(){
+    // t^2 + 1 = (t + 2)(t + 3) over GF(5)
+    assert!(ExtensionField::new(BigInt::from(5),vec![BigInt::one(),BigInt::zero(),BigInt::one()]).is_err());
+    assert!(ExtensionField::new(BigInt::from(7),vec![BigInt::one(),BigInt::zero(),BigInt::from(2)]).is_err());
+    // x^2 + x + 1 has no root mod 4, but 4 is not a prime
+    assert!(ExtensionField::new(BigInt::from(4),vec![BigInt::one(),BigInt::one(),BigInt::one()]).is_err());
+    assert!(ExtensionField::with_degree(BigInt::from(9),2).is_err());
+    assert_eq!(gf49().order(),BigInt::from(49));
+
+    // x^2 is the first candidate and x^2 + 1 the first irreducible one over GF(7)
+    assert_eq!(ExtensionField::with_degree(BigInt::from(7),2).unwrap(),gf49());
+    for (p,k) in [(2,8),(3,5),(5,1)]{
+        let field = ExtensionField::with_degree(BigInt::from(p),k).unwrap();
+        assert_eq!(field.order(),BigInt::from(p).pow(k as u32));
+        assert!(ExtensionField::new(BigInt::from(p),field.get_modulus().clone()).is_ok());
+    }
+}
+
+
+#[test]
+fn binary_field_arithmetic(){
+    // the AES field GF(2^8) = GF(2)[x] / (x^8 + x^4 + x^3 + x + 1)
+    let modulus = [1,1,0,1,1,0,0,0,1].iter().map(|&c| BigInt::from(c)).collect();
+    let field = ExtensionField::new(BigInt::from(2),modulus).unwrap();
+
+    let a = field.from_number(BigInt::from(0x53)).unwrap();
+    let b = field.from_number(BigInt::from(0xca)).unwrap();
+    assert_eq!(a.clone() * &b,field.one());
+    assert_eq!(a.modinv(),b);
+    assert_eq!((a.clone() + &b).get_number(),&BigInt::from(0x53 ^ 0xca));
+    assert_eq!(a.clone() + &a,field.zero());
+
+    let c = field.from_number(BigInt::from(0x57)).unwrap() * field.from_number(BigInt::from(0x83)).unwrap();
+    assert_eq!(c.get_number(),&BigInt::from(0xc1));
+    assert_eq!(c.sqrt().unwrap().square(),c);
+}
+
+
+#[test]
+fn extension_field_sqrt(){
+    let field = gf49();
+    let mut squares = 0;
+    for n in 0..49{
+        let a = field.from_number(BigInt::from(n)).unwrap();
+        if let Some(root) = a.sqrt(){
+            assert_eq!(root.square(),a);
+            squares += 1;
+        }
+    }
+    assert_eq!(squares,25);
+}
+
+
+#[test]
+fn curve_over_extension_field(){
+    // y^2 = x^3 + 3 has 13 points over GF(7), trace -5, so 49 + 1 - (25 - 14) = 39 over GF(49)
+    let field = gf49();
+    let a = field.zero();
+    let b = a.integer_like(3);
+
+    let mut points = vec![PointAffine::identity(a.clone(),b.clone())];
+    for n in 0..49{
+        let x = field.from_number(BigInt::from(n)).unwrap();
+        let rhs = x.square() * &x + &b;
+        if let Some(y) = rhs.sqrt(){
+            points.push(PointAffine::new(x.clone(),y.clone(),a.clone(),b.clone()));
+            if !y.is_zero(){
+                points.push(PointAffine::new(x,y.negate(),a.clone(),b.clone()));
+            }
+        }
+    }
+    assert_eq!(points.len(),39);
+
+    for p1 in points.iter(){
+        assert!(p1.is_on_curve());
+        assert!(p1.multiply(&BigInt::from(39)).is_identity());
+        assert_eq!(p1.to_jacobian().multiply(&BigInt::from(17)).to_affine(),p1.multiply(&BigInt::from(17)));
+        assert_eq!(p1.to_projective().multiply(&BigInt::from(17)).to_affine(),p1.multiply(&BigInt::from(17)));
+    }
+}
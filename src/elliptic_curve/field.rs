@@ -1,6 +1,6 @@
-use num_bigint::{BigInt, ToBigInt};
+use num_bigint::BigInt;
 use num_traits::{One, Zero, Signed, FromPrimitive};
-use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::field_traits::{FieldElementTraits,Field};
 
 
 use std::ops::{Add,Sub,Div,Mul};
@@ -18,31 +18,30 @@ pub struct FieldElement{
 impl FieldElement{
     pub fn new(number:BigInt,prime:BigInt) -> Result<FieldElement,FieldErrors>{
         if number>=prime || number.is_negative(){
-            return Err(FieldErrors::InvalidParams("Bad Parameters".to_string()))
+            Err(FieldErrors::InvalidParams("Bad Parameters".to_string()))
         }
         else{
-            return Ok(FieldElement { n: number, p: prime })
+            Ok(FieldElement { n: number, p: prime })
         }
     }
     pub fn new_from_i32(number:i32,prime:BigInt) -> Result<FieldElement,FieldErrors>{
         let number = BigInt::from_i32(number).unwrap(); 
         if number>=prime || number.is_negative(){
-            return Err(FieldErrors::InvalidParams("Bad Parameters".to_string()))
+            Err(FieldErrors::InvalidParams("Bad Parameters".to_string()))
         }
         else{
-            return Ok(FieldElement { n: number, p: prime })
+            Ok(FieldElement { n: number, p: prime })
         }
     }
 
     pub fn zero(p:BigInt)->FieldElement{
-        FieldElement { n: BigInt::from(0), p: p }
+        FieldElement { n: BigInt::from(0), p }
     }
 
     pub fn one(p:BigInt)->FieldElement{
-        FieldElement { n: BigInt::from(1), p: p }
+        FieldElement { n: BigInt::from(1), p }
     }
 
-
 }
 
 impl FieldElementTraits for FieldElement{
@@ -62,7 +61,7 @@ impl FieldElementTraits for FieldElement{
 
         while a > BigInt::one() {
             inv -= (&a / &m) * &x0;
-            a = (&a % &m);
+            a = &a % &m;
             std::mem::swap(&mut a, &mut m);
             std::mem::swap(&mut x0, &mut inv)
             }
@@ -72,6 +71,82 @@ impl FieldElementTraits for FieldElement{
 
 }
 
+impl Field for FieldElement{
+
+    fn zero_like(&self) -> FieldElement{
+        FieldElement::zero(self.p.clone())
+    }
+
+    fn one_like(&self) -> FieldElement{
+        FieldElement::one(self.p.clone())
+    }
+
+    fn integer_like(&self, n:i64) -> FieldElement{
+        let mut num = BigInt::from(n) % &self.p;
+        if num.is_negative(){
+            num += &self.p;
+        }
+        FieldElement { n: num, p: self.p.clone() }
+    }
+
+    fn is_zero(&self) -> bool{
+        self.n.is_zero()
+    }
+
+    fn order(&self) -> BigInt{
+        self.p.clone()
+    }
+
+    fn pow(&self, exponent:&BigInt) -> FieldElement{
+        if exponent.is_negative(){
+            return self.modinv().pow(&-exponent);
+        }
+        FieldElement { n: self.n.modpow(exponent,&self.p), p: self.p.clone() }
+    }
+
+    // accumulate integers and reduce once per coefficient
+    fn polynomial_product(a:&[FieldElement], b:&[FieldElement]) -> Vec<FieldElement>{
+        let mut products = vec![BigInt::zero();a.len() + b.len() - 1];
+        for (i,c1) in a.iter().enumerate(){
+            if c1.n.is_zero(){
+                continue;
+            }
+            for (j,c2) in b.iter().enumerate(){
+                products[i + j] += &c1.n * &c2.n;
+            }
+        }
+        let p = &a[0].p;
+        products.into_iter().map(|n| FieldElement { n: n % p, p: p.clone() }).collect()
+    }
+
+    fn polynomial_div_rem(a:&[FieldElement], b:&[FieldElement]) -> (Vec<FieldElement>,Vec<FieldElement>){
+        let (n,d) = (a.len() - 1,b.len() - 1);
+        let p = &b[0].p;
+        let lead_inverse = b[d].modinv().n;
+
+        let mut remainder: Vec<BigInt> = a.iter().map(|c| c.n.clone()).collect();
+        let mut quotient = vec![BigInt::zero();n - d + 1];
+        for i in (0..=n - d).rev(){
+            let q = &remainder[i + d] * &lead_inverse % p;
+            if q.is_zero(){
+                continue;
+            }
+            for (j,c) in b.iter().enumerate(){
+                remainder[i + j] = (&remainder[i + j] - &q * &c.n) % p;
+            }
+            quotient[i] = q;
+        }
+        remainder.truncate(d);
+        let reduce = |n:BigInt| {
+            let n = if n.is_negative() { n + p } else { n };
+            FieldElement { n, p: p.clone() }
+        };
+        (quotient.into_iter().map(reduce).collect(),remainder.into_iter().map(reduce).collect())
+    }
+
+}
+
+
 impl Add<FieldElement> for FieldElement{
     type Output = FieldElement;
 
@@ -356,4 +431,39 @@ fn inv_test(){
 
     assert_eq!(a_inv,a);
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn conditional_swap_test(){
+    let mut a = FieldElement{n:BigInt::from(2_u32),p:BigInt::from(7_u32)};
+    let mut b = FieldElement{n:BigInt::from(6_u32),p:BigInt::from(7_u32)};
+
+    a.conditional_swap(&mut b, 0);
+    assert_eq!(a.get_number(),&BigInt::from(2_u32));
+    assert_eq!(b.get_number(),&BigInt::from(6_u32));
+
+    a.conditional_swap(&mut b, 1);
+    assert_eq!(a.get_number(),&BigInt::from(6_u32));
+    assert_eq!(b.get_number(),&BigInt::from(2_u32));
+}
+
+
+#[test]
+fn sqrt_test(){
+    for prime in [7_u32,13,17,41,97,257]{
+        let p = BigInt::from(prime);
+        let mut squares = 0;
+        for n in 0..prime{
+            let a = FieldElement{n:BigInt::from(n),p:p.clone()};
+            match a.sqrt(){
+                Some(root) => {
+                    assert!(a.is_square());
+                    assert_eq!(&root * &root,a);
+                    squares += 1;
+                }
+                None => assert!(!a.is_square()),
+            }
+        }
+        assert_eq!(squares,prime / 2 + 1);
+    }
+}
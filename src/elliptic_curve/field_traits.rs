@@ -1,12 +1,184 @@
 use num_bigint::BigInt;
-use crate::elliptic_curve::field::FieldElement;
+use num_traits::{One, Zero};
+
+use std::fmt::Debug;
+use std::ops::{Add,Sub,Mul,Div};
+
+
 pub trait FieldElementTraits{
 
     fn get_number(&self) -> &BigInt;
-    
+
     fn get_prime(&self) -> &BigInt;
 
-    fn modinv(&self) -> FieldElement;
+    fn modinv(&self) -> Self where Self: Sized;
 }
 
 
+// Everything the coordinate layer needs from a field. Implemented by prime
+// field elements as well as by extension and binary field elements, for which
+// `get_prime` is the characteristic and `get_number` an integer encoding.
+// The short Weierstrass point types divide by 2 and 3, so they need
+// characteristic above 3 (enforced by `EllipticCurve::new`); binary fields go
+// with `LongWeierstrassCurve`.
+pub trait Field: FieldElementTraits + Clone + PartialEq + Debug
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+    + for<'a> Add<&'a Self, Output = Self> + for<'a> Sub<&'a Self, Output = Self>
+    + for<'a> Mul<&'a Self, Output = Self> + for<'a> Div<&'a Self, Output = Self>
+{
+
+    fn zero_like(&self) -> Self;
+
+    fn one_like(&self) -> Self;
+
+    // n mapped into the same field as self
+    fn integer_like(&self, n:i64) -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    // number of elements of the field
+    fn order(&self) -> BigInt;
+
+    fn characteristic(&self) -> BigInt{
+        self.get_prime().clone()
+    }
+
+    fn is_one(&self) -> bool{
+        *self == self.one_like()
+    }
+
+    fn square(&self) -> Self{
+        self.clone() * self
+    }
+
+    fn negate(&self) -> Self{
+        self.zero_like() - self.clone()
+    }
+
+    fn pow(&self, exponent:&BigInt) -> Self{
+        if exponent < &BigInt::zero(){
+            return self.modinv().pow(&-exponent);
+        }
+        let mut result = self.one_like();
+        for i in (0..exponent.bits()).rev(){
+            result = result.square();
+            if exponent.bit(i){
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    // Euler's criterion, every element is a square in characteristic 2
+    fn is_square(&self) -> bool{
+        if self.is_zero() || self.characteristic() == BigInt::from(2){
+            return true;
+        }
+        self.pow(&((self.order() - BigInt::one()) >> 1)).is_one()
+    }
+
+    // Tonelli-Shanks over a field with q elements
+    fn sqrt(&self) -> Option<Self>{
+        if self.is_zero(){
+            return Some(self.clone());
+        }
+        let q = self.order();
+        if self.characteristic() == BigInt::from(2){
+            return Some(self.pow(&(q >> 1)));
+        }
+        if !self.is_square(){
+            return None;
+        }
+
+        if &q % BigInt::from(4) == BigInt::from(3){
+            return Some(self.pow(&((q + BigInt::one()) >> 2)));
+        }
+
+        let mut odd = &q - BigInt::one();
+        let mut s = 0_u64;
+        while !odd.bit(0){
+            odd >>= 1;
+            s += 1;
+        }
+        let z = self.non_residue()?;
+
+        let mut m = s;
+        let mut c = z.pow(&odd);
+        let mut t = self.pow(&odd);
+        let mut r = self.pow(&((&odd + BigInt::one()) >> 1));
+        while !t.is_one(){
+            let mut i = 0_u64;
+            let mut t2 = t.clone();
+            while !t2.is_one(){
+                t2 = t2.square();
+                i += 1;
+            }
+            let mut b = c.clone();
+            for _ in 0..(m - i - 1){
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t = t * &c;
+            r = r * &b;
+        }
+        Some(r)
+    }
+
+    // some quadratic non-residue of the field, prime fields search 2, 3, ...
+    fn non_residue(&self) -> Option<Self>{
+        let mut k = 2;
+        let mut z = self.integer_like(k);
+        while !z.is_zero(){
+            if !z.is_square(){
+                return Some(z);
+            }
+            k += 1;
+            z = self.integer_like(k);
+        }
+        None
+    }
+
+    // coefficients of the product of two non-zero polynomials, lowest degree
+    // first. A hook for `Polynomial` so prime fields can defer the reductions
+    fn polynomial_product(a:&[Self], b:&[Self]) -> Vec<Self>{
+        let mut product = vec![a[0].zero_like();a.len() + b.len() - 1];
+        for (i,c1) in a.iter().enumerate(){
+            if c1.is_zero(){
+                continue;
+            }
+            for (j,c2) in b.iter().enumerate(){
+                product[i + j] = product[i + j].clone() + c1.clone() * c2;
+            }
+        }
+        product
+    }
+
+    // quotient and remainder of a by a non-zero b with deg a >= deg b
+    fn polynomial_div_rem(a:&[Self], b:&[Self]) -> (Vec<Self>,Vec<Self>){
+        let (n,d) = (a.len() - 1,b.len() - 1);
+        let lead_inverse = b[d].modinv();
+        let mut remainder = a.to_vec();
+        let mut quotient = vec![a[0].zero_like();n - d + 1];
+        for i in (0..=n - d).rev(){
+            let q = remainder[i + d].clone() * &lead_inverse;
+            if q.is_zero(){
+                continue;
+            }
+            for (j,c) in b.iter().enumerate(){
+                remainder[i + j] = remainder[i + j].clone() - q.clone() * c;
+            }
+            quotient[i] = q;
+        }
+        remainder.truncate(d);
+        (quotient,remainder)
+    }
+
+    // swaps self and other when choice is 1 and leaves them when it is 0,
+    // using the same arithmetic in both cases instead of a branch
+    fn conditional_swap(&mut self, other:&mut Self, choice:u8){
+        let diff = (other.clone() - self.clone()) * self.integer_like((choice & 1) as i64);
+        *self = self.clone() + &diff;
+        *other = other.clone() - &diff;
+    }
+}
@@ -0,0 +1,234 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::msm::msm_straus;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Gallant-Lambert-Vanstone endomorphism phi(x, y) = (beta x, y) of a curve with
+// a = 0, where beta is a primitive cube root of unity in F_p. On the subgroup of
+// prime order n it acts as multiplication by lambda, a cube root of unity mod n.
+// A scalar k is split as k = k1 + k2 lambda (mod n) with k1, k2 about half the
+// length of n, and k P = k1 P + k2 phi(P) is computed with Shamir's trick.
+#[derive(Debug,Clone,PartialEq)]
+pub struct GlvEndomorphism{
+    beta: FieldElement,
+    lambda: BigInt,
+    order: BigInt,
+    basis: [(BigInt,BigInt);2],
+}
+
+
+impl GlvEndomorphism{
+
+    pub fn new(beta:FieldElement, lambda:BigInt, order:BigInt) -> Result<GlvEndomorphism,FieldErrors>{
+        let one = FieldElement::one(beta.get_prime().clone());
+        if beta == one || &beta * &beta * &beta != one{
+            return Err(FieldErrors::InvalidParams("beta must be a primitive cube root of unity".to_string()));
+        }
+        if lambda.is_one() || lambda.modpow(&BigInt::from(3),&order) != BigInt::one(){
+            return Err(FieldErrors::InvalidParams("lambda must be a primitive cube root of unity mod n".to_string()));
+        }
+        let basis = lattice_basis(&lambda,&order);
+        Ok(GlvEndomorphism{beta,lambda,order,basis})
+    }
+
+    // Looks for the pair (beta, lambda) with phi(G) = lambda G
+    pub fn find(generator:&PointAffine<FieldElement>, order:&BigInt) -> Result<GlvEndomorphism,FieldErrors>{
+        let prime = generator.element_prime();
+        let betas = cube_roots_of_unity(&prime)
+            .ok_or(FieldErrors::InvalidParams("p = 2 mod 3, there is no cube root of unity".to_string()))?;
+        let lambdas = cube_roots_of_unity(order)
+            .ok_or(FieldErrors::InvalidParams("n = 2 mod 3, there is no cube root of unity".to_string()))?;
+
+        for beta in &betas{
+            let beta = FieldElement::new(beta.clone(),prime.clone())?;
+            let image = PointAffine::new(&beta * generator.get_x(),generator.get_y().clone(),generator.get_a().clone(),generator.get_b().clone());
+            for lambda in &lambdas{
+                if generator.multiply(lambda) == image{
+                    return Self::new(beta,lambda.clone(),order.clone());
+                }
+            }
+        }
+        Err(FieldErrors::Mismatch("No cube root of unity acts on the generator".to_string()))
+    }
+
+    pub fn get_beta(&self) -> &FieldElement{
+        &self.beta
+    }
+
+    pub fn get_lambda(&self) -> &BigInt{
+        &self.lambda
+    }
+
+    pub fn get_order(&self) -> &BigInt{
+        &self.order
+    }
+
+    pub fn get_basis(&self) -> &[(BigInt,BigInt);2]{
+        &self.basis
+    }
+
+    pub fn apply(&self, point:&PointAffine<FieldElement>) -> PointAffine<FieldElement>{
+        if point.is_identity(){
+            return point.clone();
+        }
+        PointAffine::new(&self.beta * point.get_x(),point.get_y().clone(),point.get_a().clone(),point.get_b().clone())
+    }
+
+    // k = k1 + k2 lambda (mod n), using the short basis (a1, b1), (a2, b2)
+    pub fn decompose(&self, k:&BigInt) -> (BigInt,BigInt){
+        let [(a1,b1),(a2,b2)] = &self.basis;
+        let k = k % &self.order;
+
+        let c1 = round_div(&(b2 * &k),&self.order);
+        let c2 = round_div(&(-b1 * &k),&self.order);
+
+        let k1 = &k - &c1 * a1 - &c2 * a2;
+        let k2 = -(&c1 * b1) - &c2 * b2;
+        (k1,k2)
+    }
+
+    // Outside the subgroup of order n phi need not act as lambda, so the
+    // decomposition is only used when every point is in it (cofactor 1).
+    pub fn multiply(&self, point:&PointAffine<FieldElement>, k:&BigInt, cofactor:&BigInt) -> PointAffine<FieldElement>{
+        if !cofactor.is_one(){
+            return point.multiply(k);
+        }
+        self.multiply_in_subgroup(point,k)
+    }
+
+    // The caller guarantees that the point lies in the subgroup of order n
+    pub fn multiply_in_subgroup(&self, point:&PointAffine<FieldElement>, k:&BigInt) -> PointAffine<FieldElement>{
+        if point.is_identity(){
+            return point.clone();
+        }
+        let (k1,k2) = self.decompose(k);
+        msm_straus(&[k1,k2],&[point.clone(),self.apply(point)]).unwrap().to_affine()
+    }
+
+}
+
+
+fn cube_roots_of_unity(modulus:&BigInt) -> Option<[BigInt;2]>{
+    let three = BigInt::from(3);
+    let exponent = modulus - BigInt::one();
+    if !(&exponent % &three).is_zero(){
+        return None;
+    }
+    let exponent = exponent / &three;
+    let mut g = BigInt::from(2);
+    while &g < modulus{
+        let root = g.modpow(&exponent,modulus);
+        if !root.is_one(){
+            let square = &root * &root % modulus;
+            return Some([root,square]);
+        }
+        g += 1;
+    }
+    None
+}
+
+
+// Extended Euclid on (n, lambda), Guide to Elliptic Curve Cryptography, Algorithm 3.74
+fn lattice_basis(lambda:&BigInt, order:&BigInt) -> [(BigInt,BigInt);2]{
+    let sqrt_n = order.sqrt();
+    let mut r = vec![order.clone(),lambda.clone()];
+    let mut t = vec![BigInt::zero(),BigInt::one()];
+
+    while !r[r.len() - 1].is_zero(){
+        let i = r.len() - 1;
+        let q = &r[i - 1] / &r[i];
+        r.push(&r[i - 1] - &q * &r[i]);
+        t.push(&t[i - 1] - &q * &t[i]);
+    }
+
+    // l is the largest index with r_l >= sqrt(n)
+    let l = r.iter().rposition(|ri| ri >= &sqrt_n).unwrap();
+    let v1 = (r[l + 1].clone(),-&t[l + 1]);
+    let norm = |i:usize| &r[i] * &r[i] + &t[i] * &t[i];
+    let v2 = if l + 2 >= r.len() || norm(l) <= norm(l + 2){
+        (r[l].clone(),-&t[l])
+    }
+    else{
+        (r[l + 2].clone(),-&t[l + 2])
+    };
+    [v1,v2]
+}
+
+
+// a / b rounded to the nearest integer, b > 0
+fn round_div(a:&BigInt, b:&BigInt) -> BigInt{
+    let numerator = a * BigInt::from(2) + b;
+    let denominator = b * BigInt::from(2);
+    let q = &numerator / &denominator;
+    if (&numerator % &denominator).is_negative(){
+        q - 1
+    }
+    else{
+        q
+    }
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::curve::EllipticCurve;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::number_theory::hex;
+
+
+#[test]
+fn glv_small_curve(){
+    // y^2 = x^3 + 3 over GF(7) has 13 points, 7 = 1 mod 3 and 13 = 1 mod 3
+    let p = BigInt::from(7);
+    let curve = EllipticCurve::new(FieldElement::zero(p.clone()),FieldElement::new(BigInt::from(3),p).unwrap()).unwrap();
+    let g = curve.point(BigInt::from(3),BigInt::from(3)).unwrap();
+    let order = BigInt::from(13);
+
+    let endomorphism = GlvEndomorphism::find(&g,&order).unwrap();
+    assert_eq!(endomorphism.apply(&g),g.multiply(endomorphism.get_lambda()));
+
+    let curve = curve.with_generator(g.clone(),order.clone(),BigInt::one()).unwrap()
+        .with_endomorphism(endomorphism.clone()).unwrap();
+
+    for k in -30..30{
+        let k = BigInt::from(k);
+        let (k1,k2) = endomorphism.decompose(&k);
+        assert!(((&k1 + &k2 * endomorphism.get_lambda() - &k) % &order).is_zero());
+        assert_eq!(curve.multiply(&g,&k),g.multiply(&k));
+    }
+}
+
+
+#[test]
+fn glv_secp256k1(){
+    let p = hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+    let n = hex("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141");
+    let curve = EllipticCurve::new(FieldElement::zero(p.clone()),FieldElement::new(BigInt::from(7),p.clone()).unwrap()).unwrap();
+    let g = curve.point(
+        hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+        hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8")).unwrap();
+
+    let beta = FieldElement::new(hex("7AE96A2B657C07106E64479EAC3434E99CF0497512F58995C1396C28719501EE"),p).unwrap();
+    let lambda = hex("5363AD4CC05C30E0A5261C028812645A122E22EA20816678DF02967C1B23BD72");
+    let endomorphism = GlvEndomorphism::new(beta,lambda,n.clone()).unwrap();
+
+    let found = GlvEndomorphism::find(&g,&n).unwrap();
+    assert_eq!(found.apply(&g),g.multiply(found.get_lambda()));
+
+    let curve = curve.with_generator(g.clone(),n.clone(),BigInt::one()).unwrap()
+        .with_endomorphism(endomorphism.clone()).unwrap();
+
+    let k = hex("C0FFEE254729296ADBF0B3A2DB0C1A7F0B8E3DA2B63E9E0C25A56B3C5AFC2B3D");
+    let (k1,k2) = endomorphism.decompose(&k);
+    assert!(k1.bits() <= 129 && k2.bits() <= 129);
+    assert_eq!(curve.multiply(&g,&k),g.multiply(&k));
+}
@@ -0,0 +1,511 @@
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field_traits::Field;
+#[cfg(test)]
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// General Weierstrass curve y^2 + a1 xy + a3 y = x^3 + a2 x^2 + a4 x + a6,
+// valid in every characteristic including 2 and 3. Invariants follow
+// Silverman, The Arithmetic of Elliptic Curves, III.1.
+#[derive(Debug,Clone,PartialEq)]
+pub struct LongWeierstrassCurve<F>{
+    coefficients: [F;5],
+}
+
+
+// (0, 0) can be a point of the curve, so infinity is a flag as for Montgomery points.
+#[derive(Debug,Clone,PartialEq)]
+pub struct LongWeierstrassPoint<F>{
+    values: [F;2],
+    curve: [F;5],
+    infinity: bool,
+}
+
+
+// Admissible change of variables x = u^2 x' + r, y = u^3 y' + s u^2 x' + t
+// with u != 0. Every isomorphism between Weierstrass curves has this shape.
+#[derive(Debug,Clone,PartialEq)]
+pub struct ChangeOfVariables<F>{
+    u: F,
+    r: F,
+    s: F,
+    t: F,
+}
+
+
+impl<F: Field> LongWeierstrassCurve<F>{
+
+    pub fn new(a1:F,a2:F,a3:F,a4:F,a6:F) -> Result<LongWeierstrassCurve<F>,FieldErrors>{
+        let coefficients = [a1,a2,a3,a4,a6];
+        if coefficients.iter().any(|c| c.get_prime() != coefficients[0].get_prime()){
+            return Err(FieldErrors::Mismatch("Curve coefficients are in different fields".to_string()));
+        }
+        let curve = LongWeierstrassCurve { coefficients };
+        if curve.discriminant().is_zero(){
+            return Err(FieldErrors::InvalidParams("Curve is singular, discriminant is zero".to_string()));
+        }
+        Ok(curve)
+    }
+
+    // y^2 = x^3 + a x + b
+    pub fn from_short(a:F,b:F) -> Result<LongWeierstrassCurve<F>,FieldErrors>{
+        let zero = a.zero_like();
+        LongWeierstrassCurve::new(zero.clone(),zero.clone(),zero,a,b)
+    }
+
+    pub fn get_a1(&self) -> &F{
+        &self.coefficients[0]
+    }
+    pub fn get_a2(&self) -> &F{
+        &self.coefficients[1]
+    }
+    pub fn get_a3(&self) -> &F{
+        &self.coefficients[2]
+    }
+    pub fn get_a4(&self) -> &F{
+        &self.coefficients[3]
+    }
+    pub fn get_a6(&self) -> &F{
+        &self.coefficients[4]
+    }
+    pub fn get_coefficients(&self) -> &[F;5]{
+        &self.coefficients
+    }
+
+    pub fn is_short(&self) -> bool{
+        self.get_a1().is_zero() && self.get_a2().is_zero() && self.get_a3().is_zero()
+    }
+
+    // [b2, b4, b6, b8]
+    pub fn b_invariants(&self) -> [F;4]{
+        let [a1,a2,a3,a4,a6] = &self.coefficients;
+        let int = |n| a1.integer_like(n);
+
+        let b2 = a1.square() + int(4) * a2;
+        let b4 = int(2) * a4 + a1.clone() * a3;
+        let b6 = a3.square() + int(4) * a6;
+        let b8 = a1.square() * a6 + int(4) * a2 * a6 - a1.clone() * a3 * a4 + a2.clone() * a3.square() - a4.square();
+        [b2,b4,b6,b8]
+    }
+
+    // [c4, c6]
+    pub fn c_invariants(&self) -> [F;2]{
+        let [b2,b4,b6,_] = self.b_invariants();
+        let int = |n| b2.integer_like(n);
+
+        let c4 = b2.square() - int(24) * &b4;
+        let c6 = int(36) * &b2 * &b4 - b2.square() * &b2 - int(216) * b6;
+        [c4,c6]
+    }
+
+    pub fn discriminant(&self) -> F{
+        let [b2,b4,b6,b8] = self.b_invariants();
+        let int = |n| b2.integer_like(n);
+
+        int(9) * &b2 * &b4 * &b6 - b2.square() * b8 - int(8) * b4.square() * b4 - int(27) * b6.square()
+    }
+
+    // c4^3 / discriminant
+    pub fn j_invariant(&self) -> F{
+        let [c4,_] = self.c_invariants();
+        c4.square() * c4 / self.discriminant()
+    }
+
+    pub fn identity(&self) -> LongWeierstrassPoint<F>{
+        LongWeierstrassPoint::identity(self.coefficients.clone())
+    }
+
+    pub fn contains(&self, point:&LongWeierstrassPoint<F>) -> bool{
+        point.curve == self.coefficients && point.is_on_curve()
+    }
+
+    pub fn point(&self, x:F, y:F) -> Result<LongWeierstrassPoint<F>,FieldErrors>{
+        let point = LongWeierstrassPoint::new(x,y,self.coefficients.clone());
+        if !point.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Point does not satisfy the curve equation".to_string()));
+        }
+        Ok(point)
+    }
+
+    // The change of variables with u = 1 that removes a1, a2 and a3, giving
+    // y^2 = x^3 - c4/48 x - c6/864. Needs 2 and 3 to be invertible.
+    pub fn short_weierstrass_isomorphism(&self) -> Result<ChangeOfVariables<F>,FieldErrors>{
+        let characteristic = self.get_a1().characteristic();
+        if characteristic == BigInt::from(2) || characteristic == BigInt::from(3){
+            return Err(FieldErrors::InvalidParams("No short Weierstrass form in characteristic 2 or 3".to_string()));
+        }
+        let [a1,_,a3,_,_] = &self.coefficients;
+        let [b2,_,_,_] = self.b_invariants();
+        let two = a1.integer_like(2);
+
+        let s = a1.negate() / &two;
+        let r = b2.negate() / a1.integer_like(12);
+        let t = (a3.clone() + r.clone() * a1).negate() / two;
+        Ok(ChangeOfVariables { u: a1.one_like(), r, s, t })
+    }
+
+    pub fn to_short_weierstrass(&self) -> Result<(ChangeOfVariables<F>,LongWeierstrassCurve<F>),FieldErrors>{
+        let change = self.short_weierstrass_isomorphism()?;
+        let short = change.apply_to_curve(self)?;
+        Ok((change,short))
+    }
+
+}
+
+
+impl<F: Field> LongWeierstrassPoint<F>{
+
+    pub fn new(x:F,y:F,curve:[F;5]) -> Self{
+        LongWeierstrassPoint{values: [x,y],curve,infinity: false}
+    }
+
+    pub fn identity(curve:[F;5]) -> Self{
+        let zero = curve[0].zero_like();
+        LongWeierstrassPoint{values: [zero.clone(),zero],curve,infinity: true}
+    }
+
+    pub fn get_x(&self) -> &F{
+        &self.values[0]
+    }
+    pub fn get_y(&self) -> &F{
+        &self.values[1]
+    }
+    pub fn get_curve(&self) -> &[F;5]{
+        &self.curve
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.infinity
+    }
+
+    pub fn is_on_curve(&self) -> bool{
+        if self.is_identity(){
+            return true;
+        }
+        let [a1,a2,a3,a4,a6] = &self.curve;
+        let (x,y) = (self.get_x(),self.get_y());
+        let lhs = y.square() + a1.clone() * x * y + a3.clone() * y;
+        let rhs = x.square() * x + a2.clone() * x.square() + a4.clone() * x + a6;
+        lhs == rhs
+    }
+
+    // -(x, y) = (x, -y - a1 x - a3)
+    pub fn negate(&self) -> Self{
+        if self.is_identity(){
+            return self.clone();
+        }
+        let [a1,_,a3,_,_] = &self.curve;
+        let (x,y) = (self.get_x(),self.get_y());
+        let y_neg = y.negate() - a1.clone() * x - a3;
+        LongWeierstrassPoint{values: [x.clone(),y_neg],curve: self.curve.clone(),infinity: false}
+    }
+
+    // Only for points of a curve with a1 = a2 = a3 = 0. The affine sentinel
+    // (0, 0) stands for infinity, which is ambiguous when a6 = 0.
+    pub fn to_affine(&self) -> Result<PointAffine<F>,FieldErrors>{
+        let [a1,a2,a3,a4,a6] = &self.curve;
+        if !(a1.is_zero() && a2.is_zero() && a3.is_zero()){
+            return Err(FieldErrors::InvalidParams("Point is not on a short Weierstrass curve".to_string()));
+        }
+        if self.is_identity(){
+            return Ok(PointAffine::identity(a4.clone(),a6.clone()));
+        }
+        Ok(PointAffine::new(self.get_x().clone(),self.get_y().clone(),a4.clone(),a6.clone()))
+    }
+
+    // x3 = l^2 + a1 l - a2 - x1 - x2, y3 = -(l + a1) x3 - v - a3 where
+    // y = l x + v is the line through the two points
+    fn add_with_line(&self, other:&Self, slope:F, intercept:F) -> Self{
+        let [a1,a2,a3,_,_] = &self.curve;
+        let x3 = slope.square() + a1.clone() * &slope - a2 - self.get_x() - other.get_x();
+        let y3 = (slope + a1).negate() * &x3 - intercept - a3;
+        LongWeierstrassPoint{values: [x3,y3],curve: self.curve.clone(),infinity: false}
+    }
+
+}
+
+
+impl<F: Field> PointAffine<F>{
+
+    pub fn to_long_weierstrass(&self) -> LongWeierstrassPoint<F>{
+        let zero = self.get_a().zero_like();
+        let curve = [zero.clone(),zero.clone(),zero,self.get_a().clone(),self.get_b().clone()];
+        if self.is_identity(){
+            return LongWeierstrassPoint::identity(curve);
+        }
+        LongWeierstrassPoint::new(self.get_x().clone(),self.get_y().clone(),curve)
+    }
+
+}
+
+
+impl<F: Field> PointOperations for LongWeierstrassPoint<F>{
+
+    fn add(&self, other: Self) -> Self{
+        if self.is_identity(){
+            return other;
+        }
+        if other.is_identity(){
+            return self.clone();
+        }
+        let (x1,y1) = (self.get_x(),self.get_y());
+        let (x2,y2) = (other.get_x(),other.get_y());
+        if x1 == x2{
+            return if y1 == y2 { self.double() } else { Self::identity(self.curve.clone()) };
+        }
+        let dx = x2.clone() - x1;
+        let slope = (y2.clone() - y1) / &dx;
+        let intercept = (y1.clone() * x2 - y2.clone() * x1) / dx;
+        self.add_with_line(&other,slope,intercept)
+    }
+
+    fn double(&self) -> Self{
+        if self.is_identity(){
+            return self.clone();
+        }
+        let [a1,a2,a3,a4,a6] = &self.curve;
+        let (x,y) = (self.get_x(),self.get_y());
+        let denominator = y.integer_like(2) * y + a1.clone() * x + a3;
+        if denominator.is_zero(){
+            return Self::identity(self.curve.clone());
+        }
+        let x2 = x.square();
+        let slope = (x2.clone() * x.integer_like(3) + a2.integer_like(2) * a2 * x + a4 - a1.clone() * y) / &denominator;
+        let intercept = (x2.negate() * x + a4.clone() * x + a6.integer_like(2) * a6 - a3.clone() * y) / denominator;
+        self.add_with_line(self,slope,intercept)
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
+        }
+        let mut q = self.clone();
+        let mut r = Self::identity(self.curve.clone());
+
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r = r.add(q.clone());
+            }
+            q = q.double();
+        }
+        r
+    }
+
+}
+
+
+impl<F: Field> ChangeOfVariables<F>{
+
+    pub fn new(u:F,r:F,s:F,t:F) -> Result<ChangeOfVariables<F>,FieldErrors>{
+        if u.is_zero(){
+            return Err(FieldErrors::InvalidParams("u must be nonzero".to_string()));
+        }
+        Ok(ChangeOfVariables { u, r, s, t })
+    }
+
+    pub fn get_u(&self) -> &F{
+        &self.u
+    }
+    pub fn get_r(&self) -> &F{
+        &self.r
+    }
+    pub fn get_s(&self) -> &F{
+        &self.s
+    }
+    pub fn get_t(&self) -> &F{
+        &self.t
+    }
+
+    // coefficients of the curve in the new variables x', y'
+    pub fn apply_to_curve(&self, curve:&LongWeierstrassCurve<F>) -> Result<LongWeierstrassCurve<F>,FieldErrors>{
+        let [a1,a2,a3,a4,a6] = curve.get_coefficients();
+        let (u,r,s,t) = (&self.u,&self.r,&self.s,&self.t);
+        let int = |n| u.integer_like(n);
+        let u_inv = u.modinv();
+        let u2_inv = u_inv.square();
+
+        let new_a1 = (a1.clone() + int(2) * s) * &u_inv;
+        let new_a2 = (a2.clone() - s.clone() * a1 + int(3) * r - s.square()) * &u2_inv;
+        let new_a3 = (a3.clone() + r.clone() * a1 + int(2) * t) * (u2_inv.clone() * &u_inv);
+        let new_a4 = (a4.clone() - s.clone() * a3 + int(2) * r * a2 - (t.clone() + r.clone() * s) * a1
+            + int(3) * r.square() - int(2) * s * t) * u2_inv.square();
+        let new_a6 = (a6.clone() + r.clone() * a4 + r.square() * a2 + r.square() * r - t.clone() * a3
+            - t.square() - r.clone() * t * a1) * (u2_inv.square() * &u2_inv);
+        LongWeierstrassCurve::new(new_a1,new_a2,new_a3,new_a4,new_a6)
+    }
+
+    // (x, y) -> (x', y') = ((x - r) / u^2, (y - s (x - r) - t) / u^3)
+    pub fn apply_to_point(&self, point:&LongWeierstrassPoint<F>, curve:&LongWeierstrassCurve<F>) -> Result<LongWeierstrassPoint<F>,FieldErrors>{
+        let target = self.apply_to_curve(curve)?;
+        if !curve.contains(point){
+            return Err(FieldErrors::Mismatch("Point is not on the source curve".to_string()));
+        }
+        if point.is_identity(){
+            return Ok(target.identity());
+        }
+        let u2 = self.u.square();
+        let x_shift = point.get_x().clone() - &self.r;
+        let y = (point.get_y().clone() - self.s.clone() * &x_shift - &self.t) / (u2.clone() * &self.u);
+        target.point(x_shift / u2,y)
+    }
+
+    // (x', y') -> (x, y) = (u^2 x' + r, u^3 y' + s u^2 x' + t)
+    pub fn invert_point(&self, point:&LongWeierstrassPoint<F>, curve:&LongWeierstrassCurve<F>) -> Result<LongWeierstrassPoint<F>,FieldErrors>{
+        let target = self.apply_to_curve(curve)?;
+        if !target.contains(point){
+            return Err(FieldErrors::Mismatch("Point is not on the transformed curve".to_string()));
+        }
+        if point.is_identity(){
+            return Ok(curve.identity());
+        }
+        let u2 = self.u.square();
+        let u2x = u2.clone() * point.get_x();
+        let x = u2x.clone() + &self.r;
+        let y = u2 * &self.u * point.get_y() + self.s.clone() * u2x + &self.t;
+        curve.point(x,y)
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::extension_field::ExtensionField;
+#[cfg(test)]
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[cfg(test)]
+fn all_points<F: Field>(curve:&LongWeierstrassCurve<F>, elements:&[F]) -> Vec<LongWeierstrassPoint<F>>{
+    let mut points = vec![curve.identity()];
+    for x in elements{
+        for y in elements{
+            if let Ok(point) = curve.point(x.clone(),y.clone()){
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+
+#[cfg(test)]
+fn curve_11a2(p:i32) -> LongWeierstrassCurve<FieldElement>{
+    // LMFDB 11.a2, y^2 + y = x^3 - x^2 - 10x - 20
+    LongWeierstrassCurve::new(field(0,p),field(-1,p),field(1,p),field(-10,p),field(-20,p)).unwrap()
+}
+
+
+#[test]
+fn invariants_of_11a2(){
+    let curve = curve_11a2(101);
+
+    assert_eq!(curve.b_invariants(),[field(-4,101),field(-20,101),field(-79,101),field(-21,101)]);
+    assert_eq!(curve.c_invariants(),[field(496,101),field(20008,101)]);
+    assert_eq!(curve.discriminant(),field(-161051,101));
+    // j = -122023936 / 161051
+    assert_eq!(curve.j_invariant(),field(-122023936 % 101,101) / field(161051 % 101,101));
+
+    // 11 divides the discriminant, the reduction mod 11 is singular
+    assert!(LongWeierstrassCurve::new(field(0,11),field(-1,11),field(1,11),field(-10,11),field(-20,11)).is_err());
+}
+
+
+#[test]
+fn group_law_of_11a2(){
+    // a_13 = 4 and a_101 = 2, so the curve has 10 and 100 points
+    for (p,order) in [(13,10),(101,100)]{
+        let curve = curve_11a2(p);
+        let elements: Vec<FieldElement> = (0..p).map(|n| field(n,p)).collect();
+        let points = all_points(&curve,&elements);
+        assert_eq!(points.len(),order);
+
+        for p1 in points.iter().step_by(3){
+            assert!(p1.multiply(&BigInt::from(order)).is_identity());
+            assert!(p1.add(p1.negate()).is_identity());
+            for p2 in points.iter().step_by(7){
+                assert_eq!(p1.add(p2.clone()),p2.add(p1.clone()));
+                assert!(curve.contains(&p1.add(p2.clone())));
+            }
+        }
+    }
+}
+
+
+#[test]
+fn binary_field_curve(){
+    // y^2 + xy = x^3 + x^2 + t over GF(2^4) = GF(2)[t] / (t^4 + t + 1), discriminant a6 = t
+    let modulus = [1,1,0,0,1].iter().map(|&c| BigInt::from(c)).collect();
+    let gf16 = ExtensionField::new(BigInt::from(2),modulus).unwrap();
+    let elements: Vec<_> = (0..16).map(|n| gf16.from_number(BigInt::from(n)).unwrap()).collect();
+    let (zero,one,t) = (elements[0].clone(),elements[1].clone(),elements[2].clone());
+
+    let curve = LongWeierstrassCurve::new(one.clone(),one.clone(),zero.clone(),zero.clone(),t.clone()).unwrap();
+    assert_eq!(curve.discriminant(),t);
+    assert_eq!(curve.j_invariant(),t.modinv());
+    assert!(curve.short_weierstrass_isomorphism().is_err());
+    assert!(LongWeierstrassCurve::new(one.clone(),one,zero.clone(),zero.clone(),zero).is_err());
+
+    let points = all_points(&curve,&elements);
+    let order = BigInt::from(points.len());
+    // Hasse bound |#E - 17| <= 2 sqrt(16)
+    assert!((points.len() as i64 - 17).abs() <= 8);
+    for p1 in points.iter(){
+        assert!(p1.multiply(&order).is_identity());
+        assert_eq!(p1.double(),p1.add(p1.clone()));
+        assert!(p1.add(p1.negate()).is_identity());
+    }
+}
+
+
+#[test]
+fn change_of_variables(){
+    let curve = curve_11a2(101);
+    let change = ChangeOfVariables::new(field(3,101),field(7,101),field(-5,101),field(11,101)).unwrap();
+    let target = change.apply_to_curve(&curve).unwrap();
+
+    // j is invariant and the discriminant scales by u^-12
+    assert_eq!(target.j_invariant(),curve.j_invariant());
+    assert_eq!(target.discriminant() * field(3,101).pow(&BigInt::from(12)),curve.discriminant());
+
+    let elements: Vec<FieldElement> = (0..101).map(|n| field(n,101)).collect();
+    let points = all_points(&curve,&elements);
+    for p1 in points.iter().step_by(5){
+        let q1 = change.apply_to_point(p1,&curve).unwrap();
+        assert_eq!(&change.invert_point(&q1,&curve).unwrap(),p1);
+        for p2 in points.iter().step_by(11){
+            let q2 = change.apply_to_point(p2,&curve).unwrap();
+            assert_eq!(change.apply_to_point(&p1.add(p2.clone()),&curve).unwrap(),q1.add(q2));
+        }
+    }
+}
+
+
+#[test]
+fn short_form_of_11a2(){
+    let curve = curve_11a2(101);
+    let (change,short) = curve.to_short_weierstrass().unwrap();
+    assert!(short.is_short());
+    assert_eq!(short.get_a4(),&(field(-496,101) / field(48,101)));
+    assert_eq!(short.get_a6(),&(field(-20008,101) / field(864,101)));
+
+    let elements: Vec<FieldElement> = (0..101).map(|n| field(n,101)).collect();
+    for p1 in all_points(&curve,&elements).iter().step_by(4){
+        let q1 = change.apply_to_point(p1,&curve).unwrap();
+        let affine = q1.to_affine().unwrap();
+        assert!(affine.is_on_curve());
+        assert_eq!(affine.multiply(&BigInt::from(7)).to_long_weierstrass(),q1.multiply(&BigInt::from(7)));
+    }
+    assert!(curve.identity().to_affine().is_err());
+}
+
@@ -0,0 +1,366 @@
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::Field;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::montgomery::{MontgomeryCurve,MontgomeryPoint};
+use crate::elliptic_curve::edwards::{EdwardsCurve,EdwardsPoint};
+
+
+// Birational equivalences between the curve models. All of them need an odd
+// characteristic, the Weierstrass maps also one different from 3.
+
+
+// B v^2 = u^3 + A u^2 + u and a x^2 + y^2 = 1 + d x^2 y^2 are equivalent when
+// A = 2 (a + d) / (a - d) and B (a - d) / 4 = s^2 is a square, through
+//   (u, v) -> (x, y) = (u / (s v), (u - 1) / (u + 1))
+//   (x, y) -> (u, v) = ((1 + y) / (1 - y), (1 + y) / (s x (1 - y)))
+// The two choices of s give the two equivalences, which differ by negation.
+#[derive(Debug,Clone,PartialEq)]
+pub struct MontgomeryEdwardsMap<F>{
+    montgomery: MontgomeryCurve<F>,
+    edwards: EdwardsCurve<F>,
+    scale: F,
+}
+
+
+// B v^2 = u^3 + A u^2 + u is isomorphic to y^2 = x^3 + a x + b with
+// a = (3 - A^2) / (3 B^2) and b = (2 A^3 - 9 A) / (27 B^3), through
+//   (u, v) -> (x, y) = (u / B + A / (3 B), v / B)
+// The Montgomery point (0, 0) goes to (A / (3 B), 0), so a Weierstrass curve
+// comes from a Montgomery curve only if it has a point of order two.
+#[derive(Debug,Clone,PartialEq)]
+pub struct MontgomeryWeierstrassMap<F>{
+    montgomery: MontgomeryCurve<F>,
+    a: F,
+    b: F,
+}
+
+
+// A = 2 (a + d) / (a - d), B = 4 / (a - d), the equivalence with s = 1
+pub fn edwards_to_montgomery_curve<F: Field>(curve:&EdwardsCurve<F>) -> Result<MontgomeryCurve<F>,FieldErrors>{
+    let (a,d) = (curve.get_a(),curve.get_d());
+    let a_minus_d = a.clone() - d;
+    let big_a = (a.clone() + d) * a.integer_like(2) / &a_minus_d;
+    let big_b = a.integer_like(4) / a_minus_d;
+    MontgomeryCurve::new(big_a,big_b)
+}
+
+
+// a = (A + 2) / B, d = (A - 2) / B, the equivalence with s = 1
+pub fn montgomery_to_edwards_curve<F: Field>(curve:&MontgomeryCurve<F>) -> Result<EdwardsCurve<F>,FieldErrors>{
+    let (big_a,big_b) = (curve.get_a(),curve.get_b());
+    let a = (big_a.clone() + big_a.integer_like(2)) / big_b;
+    let d = (big_a.clone() - big_a.integer_like(2)) / big_b;
+    EdwardsCurve::new(a,d)
+}
+
+
+impl<F: Field> MontgomeryEdwardsMap<F>{
+
+    pub fn new(montgomery:MontgomeryCurve<F>, edwards:EdwardsCurve<F>) -> Result<MontgomeryEdwardsMap<F>,FieldErrors>{
+        let natural = edwards_to_montgomery_curve(&edwards)?;
+        if natural.get_a() != montgomery.get_a(){
+            return Err(FieldErrors::Mismatch("Curves have different A = 2 (a + d) / (a - d)".to_string()));
+        }
+        // B s^2 = 4 / (a - d), the B of the natural equivalent
+        let scale = (montgomery.get_b().clone() / natural.get_b()).sqrt()
+            .ok_or(FieldErrors::Mismatch("Curves are quadratic twists of each other".to_string()))?;
+        Ok(MontgomeryEdwardsMap { montgomery, edwards, scale })
+    }
+
+    pub fn get_montgomery(&self) -> &MontgomeryCurve<F>{
+        &self.montgomery
+    }
+
+    pub fn get_edwards(&self) -> &EdwardsCurve<F>{
+        &self.edwards
+    }
+
+    pub fn get_scale(&self) -> &F{
+        &self.scale
+    }
+
+    // Exceptional points: O -> (0, 1) and (0, 0) -> (0, -1). The other points
+    // with v = 0 or u = -1 lie at infinity of the Edwards model and have no
+    // affine image, they only exist when the Edwards law is incomplete.
+    pub fn to_edwards(&self, point:&MontgomeryPoint<F>) -> Result<EdwardsPoint<F>,FieldErrors>{
+        if !self.is_montgomery_point(point){
+            return Err(FieldErrors::Mismatch("Point is not on the Montgomery curve of the map".to_string()));
+        }
+        let edwards = &self.edwards;
+        if point.is_identity(){
+            return Ok(edwards.identity());
+        }
+        let (u,v) = (point.get_x(),point.get_y());
+        let one = u.one_like();
+        if u.is_zero(){
+            return edwards.point(u.zero_like(),one.negate());
+        }
+        let u_plus_one = u.clone() + &one;
+        if v.is_zero() || u_plus_one.is_zero(){
+            return Err(FieldErrors::PointNotOnCurve("Point maps to infinity on the Edwards curve".to_string()));
+        }
+        let x = u.clone() / (self.scale.clone() * v);
+        let y = (u.clone() - one) / u_plus_one;
+        edwards.point(x,y)
+    }
+
+    // Exceptional points: (0, 1) -> O and (0, -1) -> (0, 0), every affine
+    // Edwards point has an image.
+    pub fn to_montgomery(&self, point:&EdwardsPoint<F>) -> Result<MontgomeryPoint<F>,FieldErrors>{
+        if !self.edwards.contains(point){
+            return Err(FieldErrors::Mismatch("Point is not on the Edwards curve of the map".to_string()));
+        }
+        let montgomery = &self.montgomery;
+        if point.is_identity(){
+            return Ok(montgomery.identity());
+        }
+        let (x,y) = (point.get_x(),point.get_y());
+        if x.is_zero(){
+            return montgomery.point(x.zero_like(),x.zero_like());
+        }
+        let one = y.one_like();
+        let u = (one.clone() + y) / (one - y);
+        let v = u.clone() / (self.scale.clone() * x);
+        montgomery.point(u,v)
+    }
+
+    fn is_montgomery_point(&self, point:&MontgomeryPoint<F>) -> bool{
+        point.get_a() == self.montgomery.get_a() && point.get_b() == self.montgomery.get_b() && point.is_on_curve()
+    }
+
+}
+
+
+impl<F: Field> MontgomeryWeierstrassMap<F>{
+
+    pub fn new(montgomery:MontgomeryCurve<F>) -> MontgomeryWeierstrassMap<F>{
+        let (big_a,big_b) = (montgomery.get_a(),montgomery.get_b());
+        let three = big_a.integer_like(3);
+        let big_a2 = big_a.square();
+        let big_b2 = big_b.square();
+
+        let a = (three.clone() - &big_a2) / (three * &big_b2);
+        let b = (big_a2 * big_a.integer_like(2) - big_a.integer_like(9)) * big_a / (big_a.integer_like(27) * big_b2 * big_b);
+        MontgomeryWeierstrassMap { montgomery, a, b }
+    }
+
+    // From y^2 = x^3 + a x + b and a point (alpha, 0) of order two with
+    // 3 alpha^2 + a = 1 / s^2 a square: A = 3 alpha s, B = s.
+    pub fn from_weierstrass(two_torsion:&PointAffine<F>) -> Result<MontgomeryWeierstrassMap<F>,FieldErrors>{
+        let alpha = two_torsion.get_x();
+        if two_torsion.is_identity() || !two_torsion.get_y().is_zero() || !two_torsion.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Not a point of order two".to_string()));
+        }
+        let slope = alpha.square() * alpha.integer_like(3) + two_torsion.get_a();
+        let root = slope.sqrt()
+            .ok_or(FieldErrors::InvalidParams("3 alpha^2 + a is not a square, no Montgomery form".to_string()))?;
+        let s = root.modinv();
+        let montgomery = MontgomeryCurve::new(alpha.integer_like(3) * alpha * &s,s)?;
+        Ok(MontgomeryWeierstrassMap::new(montgomery))
+    }
+
+    pub fn get_montgomery(&self) -> &MontgomeryCurve<F>{
+        &self.montgomery
+    }
+
+    pub fn get_a(&self) -> &F{
+        &self.a
+    }
+
+    pub fn get_b(&self) -> &F{
+        &self.b
+    }
+
+    pub fn to_weierstrass(&self, point:&MontgomeryPoint<F>) -> Result<PointAffine<F>,FieldErrors>{
+        if point.get_a() != self.montgomery.get_a() || point.get_b() != self.montgomery.get_b() || !point.is_on_curve(){
+            return Err(FieldErrors::Mismatch("Point is not on the Montgomery curve of the map".to_string()));
+        }
+        if point.is_identity(){
+            return Ok(PointAffine::identity(self.a.clone(),self.b.clone()));
+        }
+        let (big_a,big_b) = (self.montgomery.get_a(),self.montgomery.get_b());
+        let x = (point.get_x().clone() + big_a.clone() / big_a.integer_like(3)) / big_b;
+        let y = point.get_y().clone() / big_b;
+        Ok(PointAffine::new(x,y,self.a.clone(),self.b.clone()))
+    }
+
+    pub fn to_montgomery(&self, point:&PointAffine<F>) -> Result<MontgomeryPoint<F>,FieldErrors>{
+        if point.get_a() != &self.a || point.get_b() != &self.b || !point.is_on_curve(){
+            return Err(FieldErrors::Mismatch("Point is not on the Weierstrass curve of the map".to_string()));
+        }
+        if point.is_identity(){
+            return Ok(self.montgomery.identity());
+        }
+        let (big_a,big_b) = (self.montgomery.get_a(),self.montgomery.get_b());
+        let u = point.get_x().clone() * big_b - big_a.clone() / big_a.integer_like(3);
+        let v = point.get_y().clone() * big_b;
+        self.montgomery.point(u,v)
+    }
+
+}
+
+
+impl MontgomeryWeierstrassMap<FieldElement>{
+
+    pub fn weierstrass_curve(&self) -> Result<EllipticCurve,FieldErrors>{
+        EllipticCurve::new(self.a.clone(),self.b.clone())
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use num_bigint::BigInt;
+#[cfg(test)]
+use crate::elliptic_curve::number_theory::hex;
+#[cfg(test)]
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+#[cfg(test)]
+use crate::elliptic_curve::{edwards,xdh};
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[cfg(test)]
+fn montgomery_points(curve:&MontgomeryCurve<FieldElement>, p:i32) -> Vec<MontgomeryPoint<FieldElement>>{
+    let mut points = vec![curve.identity()];
+    for u in 0..p{
+        for v in 0..p{
+            if let Ok(point) = curve.point(field(u,p),field(v,p)){
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+
+#[test]
+fn small_curve_maps_are_homomorphisms(){
+    // A = 4, B = 5 over GF(101): (A + 2) / B is a square and (A - 2) / B is not,
+    // so the Edwards form is complete and every Montgomery point has an image
+    let montgomery = MontgomeryCurve::new(field(4,101),field(5,101)).unwrap();
+    let edwards = montgomery_to_edwards_curve(&montgomery).unwrap();
+    assert!(edwards.is_complete());
+    assert_eq!(edwards_to_montgomery_curve(&edwards).unwrap(),montgomery);
+
+    let to_edwards = MontgomeryEdwardsMap::new(montgomery.clone(),edwards).unwrap();
+    assert!(to_edwards.get_scale().is_one() || to_edwards.get_scale().negate().is_one());
+    let to_weierstrass = MontgomeryWeierstrassMap::new(montgomery.clone());
+
+    let points = montgomery_points(&montgomery,101);
+    for p1 in points.iter(){
+        let w1 = to_weierstrass.to_weierstrass(p1).unwrap();
+        let e1 = to_edwards.to_edwards(p1).unwrap();
+        assert!(w1.is_on_curve());
+        assert_eq!(&to_weierstrass.to_montgomery(&w1).unwrap(),p1);
+        assert_eq!(&to_edwards.to_montgomery(&e1).unwrap(),p1);
+
+        for p2 in points.iter().step_by(9){
+            let sum = p1.add(p2.clone());
+            let w2 = to_weierstrass.to_weierstrass(p2).unwrap();
+            let e2 = to_edwards.to_edwards(p2).unwrap();
+            assert_eq!(to_weierstrass.to_weierstrass(&sum).unwrap(),w1.clone() + w2);
+            assert_eq!(to_edwards.to_edwards(&sum).unwrap(),e1.add(e2));
+        }
+    }
+}
+
+
+#[test]
+fn exceptional_points(){
+    let montgomery = MontgomeryCurve::new(field(3,101),field(5,101)).unwrap();
+    let edwards = montgomery_to_edwards_curve(&montgomery).unwrap();
+    let map = MontgomeryEdwardsMap::new(montgomery.clone(),edwards.clone()).unwrap();
+
+    let two_torsion = montgomery.point(field(0,101),field(0,101)).unwrap();
+    let minus_one = edwards.point(field(0,101),field(100,101)).unwrap();
+    assert_eq!(map.to_edwards(&montgomery.identity()).unwrap(),edwards.identity());
+    assert_eq!(map.to_edwards(&two_torsion).unwrap(),minus_one);
+    assert_eq!(map.to_montgomery(&edwards.identity()).unwrap(),montgomery.identity());
+    assert_eq!(map.to_montgomery(&minus_one).unwrap(),two_torsion);
+
+    // the other points of order two have v = 0 and sit at infinity on the Edwards side
+    for point in montgomery_points(&montgomery,101){
+        if point.get_y().is_zero() && !point.get_x().is_zero(){
+            assert!(map.to_edwards(&point).is_err());
+        }
+    }
+    assert!(MontgomeryWeierstrassMap::from_weierstrass(&PointAffine::identity(field(1,101),field(1,101))).is_err());
+}
+
+
+#[test]
+fn montgomery_origin_with_a_zero(){
+    // A = 0 gives b = 0 on the Weierstrass side, and (0, 0) maps to the affine point (0, 0)
+    let montgomery = MontgomeryCurve::new(field(0,101),field(1,101)).unwrap();
+    let map = MontgomeryWeierstrassMap::new(montgomery.clone());
+    assert!(map.get_b().is_zero());
+
+    let origin = montgomery.point(field(0,101),field(0,101)).unwrap();
+    let image = map.to_weierstrass(&origin).unwrap();
+    assert!(!image.is_identity());
+    assert_eq!(image.get_values(),&[field(0,101),field(0,101)]);
+    assert_eq!(map.to_montgomery(&image).unwrap(),origin);
+    assert!(map.to_weierstrass(&montgomery.identity()).unwrap().is_identity());
+    for point in montgomery_points(&montgomery,101){
+        assert_eq!(map.to_montgomery(&map.to_weierstrass(&point).unwrap()).unwrap(),point);
+    }
+}
+
+
+#[cfg(test)]
+fn hex_element(s:&str, p:&BigInt) -> FieldElement{
+    FieldElement::new(hex(s),p.clone()).unwrap()
+}
+
+
+#[test]
+fn curve25519_ed25519_wei25519(){
+    let curve25519 = xdh::curve25519();
+    let ed25519 = edwards::ed25519();
+    let p = curve25519.get_a().get_prime().clone();
+
+    // RFC 7748 section 4.1, the base point u = 9 corresponds to the Ed25519 base point
+    let v = hex_element("20AE19A1B8A086B4E01EDD2C7748D14C923D4D7E6D7C61B229E9C5A27ECED3D9",&p);
+    let base = curve25519.point(FieldElement::new(BigInt::from(9),p.clone()).unwrap(),v).unwrap();
+    let generator = ed25519.get_generator().unwrap().clone();
+
+    let to_edwards = MontgomeryEdwardsMap::new(curve25519.clone(),ed25519.clone()).unwrap();
+    let mapped = to_edwards.to_edwards(&base).unwrap();
+    assert!(mapped == generator || mapped == generator.negate());
+    assert_eq!(to_edwards.to_montgomery(&mapped).unwrap(),base);
+
+    // draft-ietf-lwig-curve-representations, Wei25519
+    let to_weierstrass = MontgomeryWeierstrassMap::new(curve25519.clone());
+    assert_eq!(to_weierstrass.get_a(),&hex_element("2AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA984914A144",&p));
+    assert_eq!(to_weierstrass.get_b(),&hex_element("7B425ED097B425ED097B425ED097B425ED097B425ED097B4260B5E9C7710C864",&p));
+    let wei_base = to_weierstrass.to_weierstrass(&base).unwrap();
+    assert_eq!(wei_base.get_x(),&hex_element("2AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAD245A",&p));
+    assert!(to_weierstrass.weierstrass_curve().unwrap().contains(&wei_base));
+
+    // Ed25519 -> Curve25519 -> Wei25519 and back
+    let k = BigInt::from(123456789);
+    let edwards_point = generator.multiply(&k);
+    let montgomery_point = to_edwards.to_montgomery(&edwards_point).unwrap();
+    let weierstrass_point = to_weierstrass.to_weierstrass(&montgomery_point).unwrap();
+    assert_eq!(to_weierstrass.to_montgomery(&weierstrass_point).unwrap(),montgomery_point);
+    assert_eq!(to_edwards.to_edwards(&montgomery_point).unwrap(),edwards_point);
+    let sign = if mapped == generator { 1 } else { -1 };
+    assert_eq!(weierstrass_point,wei_base.multiply(&(k * sign)));
+
+    // Wei25519 back to a Montgomery curve through its point of order two (A / 3, 0)
+    let alpha = curve25519.get_a().clone() / FieldElement::new(BigInt::from(3),p.clone()).unwrap();
+    let two_torsion = PointAffine::new(alpha,FieldElement::zero(p.clone()),to_weierstrass.get_a().clone(),to_weierstrass.get_b().clone());
+    let back = MontgomeryWeierstrassMap::from_weierstrass(&two_torsion).unwrap();
+    assert_eq!(back.get_a(),to_weierstrass.get_a());
+    assert_eq!(back.get_montgomery().get_a().square(),curve25519.get_a().square());
+}
@@ -1,5 +1,22 @@
 pub mod field_traits;
 pub mod field;
+pub mod extension_field;
 pub mod point;
 pub mod errors;
-pub mod curve;
\ No newline at end of file
+pub mod curve;
+pub mod scalar;
+pub mod glv;
+pub mod curves;
+pub mod montgomery;
+pub mod xdh;
+pub mod edwards;
+pub mod maps;
+pub mod long_weierstrass;
+pub mod number_theory;
+pub mod counting;
+pub mod torsion;
+pub mod subgroup;
+pub mod twist;
+pub mod audit;
+#[cfg(test)]
+pub mod test_helpers;
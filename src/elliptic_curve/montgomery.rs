@@ -0,0 +1,271 @@
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field_traits::Field;
+#[cfg(test)]
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Montgomery curve B y^2 = x^3 + A x^2 + x, nonsingular when B (A^2 - 4) != 0.
+#[derive(Debug,Clone,PartialEq)]
+pub struct MontgomeryCurve<F>{
+    a: F,
+    b: F,
+}
+
+
+// (0, 0) is the point of order two on every Montgomery curve, so the point at
+// infinity is carried as a flag instead of a sentinel coordinate.
+#[derive(Debug,Clone,PartialEq)]
+pub struct MontgomeryPoint<F>{
+    values: [F;2],
+    curve: [F;2],
+    infinity: bool,
+}
+
+
+impl<F: Field> MontgomeryCurve<F>{
+
+    pub fn new(a:F,b:F) -> Result<MontgomeryCurve<F>,FieldErrors>{
+        if a.get_prime() != b.get_prime(){
+            return Err(FieldErrors::Mismatch("Curve coefficients are in different fields".to_string()));
+        }
+        if b.is_zero() || (a.square() - a.integer_like(4)).is_zero(){
+            return Err(FieldErrors::InvalidParams("Curve is singular, B (A^2 - 4) = 0".to_string()));
+        }
+        Ok(MontgomeryCurve { a, b })
+    }
+
+    pub fn get_a(&self) -> &F{
+        &self.a
+    }
+
+    pub fn get_b(&self) -> &F{
+        &self.b
+    }
+
+    pub fn identity(&self) -> MontgomeryPoint<F>{
+        MontgomeryPoint::identity(self.a.clone(),self.b.clone())
+    }
+
+    pub fn point(&self, x:F, y:F) -> Result<MontgomeryPoint<F>,FieldErrors>{
+        let point = MontgomeryPoint::new(x,y,self.a.clone(),self.b.clone());
+        if !point.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Point does not satisfy the curve equation".to_string()));
+        }
+        Ok(point)
+    }
+
+    // (A - 2) / 4, the constant of the ladder doubling step
+    pub fn a24(&self) -> F{
+        (self.a.clone() - self.a.integer_like(2)) / self.a.integer_like(4)
+    }
+
+    // x-only Montgomery ladder of RFC 7748 section 5. Returns the x coordinate
+    // of [k](u, v) as X/Z, with the point at infinity mapped to 0. Runs `bits`
+    // iterations regardless of k, u may be on the curve or on its twist.
+    pub fn ladder(&self, k:&BigInt, u:&F, bits:u64) -> F{
+        let a24 = self.a24();
+        let magnitude = k.magnitude();
+
+        let x1 = u.clone();
+        let (mut x2,mut z2) = (u.one_like(),u.zero_like());
+        let (mut x3,mut z3) = (u.clone(),u.one_like());
+        let mut swap = 0_u8;
+
+        for t in (0..bits).rev(){
+            let k_t = magnitude.bit(t) as u8;
+            swap ^= k_t;
+            x2.conditional_swap(&mut x3,swap);
+            z2.conditional_swap(&mut z3,swap);
+            swap = k_t;
+
+            let a = x2.clone() + &z2;
+            let aa = a.square();
+            let b = x2 - &z2;
+            let bb = b.square();
+            let e = aa.clone() - &bb;
+            let c = x3.clone() + &z3;
+            let d = x3 - &z3;
+            let da = d * a;
+            let cb = c * b;
+            x3 = (da.clone() + &cb).square();
+            z3 = x1.clone() * (da - cb).square();
+            x2 = aa.clone() * bb;
+            z2 = e.clone() * (aa + a24.clone() * e);
+        }
+        x2.conditional_swap(&mut x3,swap);
+        z2.conditional_swap(&mut z3,swap);
+
+        // z^(q-2) is the inverse of z and 0 for z = 0
+        x2 * z2.pow(&(z2.order() - BigInt::from(2)))
+    }
+
+}
+
+
+impl<F: Field> MontgomeryPoint<F>{
+
+    pub fn new(x:F,y:F,a:F,b:F) -> Self{
+        MontgomeryPoint{values: [x,y],curve: [a,b],infinity: false}
+    }
+
+    pub fn identity(a:F,b:F) -> Self{
+        MontgomeryPoint{values: [a.zero_like(),a.zero_like()],curve: [a,b],infinity: true}
+    }
+
+    pub fn get_x(&self) -> &F{
+        &self.values[0]
+    }
+    pub fn get_y(&self) -> &F{
+        &self.values[1]
+    }
+    pub fn get_a(&self) -> &F{
+        &self.curve[0]
+    }
+    pub fn get_b(&self) -> &F{
+        &self.curve[1]
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.infinity
+    }
+
+    pub fn is_on_curve(&self) -> bool{
+        if self.is_identity(){
+            return true;
+        }
+        let (x,y) = (self.get_x(),self.get_y());
+        let x2 = x.square();
+        self.get_b().clone() * y.square() == x2.clone() * x + self.get_a().clone() * x2 + x
+    }
+
+    pub fn negate(&self) -> Self{
+        if self.is_identity(){
+            return self.clone();
+        }
+        MontgomeryPoint{values: [self.get_x().clone(),self.get_y().negate()],curve: self.curve.clone(),infinity: false}
+    }
+
+    // x3 = B l^2 - A - x1 - x2, y3 = l (x1 - x3) - y1 for the chord or tangent slope l
+    fn add_with_slope(&self, other:&Self, slope:F) -> Self{
+        let x3 = self.get_b().clone() * slope.square() - self.get_a() - self.get_x() - other.get_x();
+        let y3 = slope * (self.get_x().clone() - &x3) - self.get_y();
+        MontgomeryPoint{values: [x3,y3],curve: self.curve.clone(),infinity: false}
+    }
+
+}
+
+
+impl<F: Field> PointOperations for MontgomeryPoint<F>{
+
+    fn add(&self, other: Self) -> Self{
+        if self.is_identity(){
+            return other;
+        }
+        if other.is_identity(){
+            return self.clone();
+        }
+        if self.get_x() == other.get_x(){
+            if self.get_y() == other.get_y(){
+                return self.double();
+            }
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let slope = (other.get_y().clone() - self.get_y()) / (other.get_x().clone() - self.get_x());
+        self.add_with_slope(&other,slope)
+    }
+
+    // l = (3 x^2 + 2 A x + 1) / (2 B y)
+    fn double(&self) -> Self{
+        if self.is_identity() || self.get_y().is_zero(){
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let x = self.get_x();
+        let numerator = x.square() * x.integer_like(3) + x.integer_like(2) * self.get_a() * x + x.one_like();
+        let denominator = x.integer_like(2) * self.get_b() * self.get_y();
+        self.add_with_slope(self,numerator / denominator)
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
+        }
+        let mut q = self.clone();
+        let mut r = Self::identity(self.get_a().clone(),self.get_b().clone());
+
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r = r.add(q.clone());
+            }
+            q = q.double();
+        }
+        r
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[cfg(test)]
+fn all_points(curve:&MontgomeryCurve<FieldElement>, p:i32) -> Vec<MontgomeryPoint<FieldElement>>{
+    let mut points = vec![curve.identity()];
+    for x in 0..p{
+        for y in 0..p{
+            if let Ok(point) = curve.point(field(x,p),field(y,p)){
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+
+#[test]
+fn montgomery_rejects_singular_curves(){
+    assert!(MontgomeryCurve::new(field(2,101),field(1,101)).is_err());
+    assert!(MontgomeryCurve::new(field(99,101),field(1,101)).is_err());
+    assert!(MontgomeryCurve::new(field(3,101),field(0,101)).is_err());
+    assert!(MontgomeryCurve::new(field(3,101),field(1,101)).is_ok());
+}
+
+
+#[test]
+fn montgomery_group_law(){
+    let curve = MontgomeryCurve::new(field(3,101),field(5,101)).unwrap();
+    let points = all_points(&curve,101);
+    let order = BigInt::from(points.len());
+
+    // every Montgomery curve has a point of order two and 4 divides the group order
+    assert!(points.contains(&curve.point(field(0,101),field(0,101)).unwrap()));
+    assert_eq!(points.len() % 4,0);
+
+    for p1 in points.iter(){
+        assert!(p1.multiply(&order).is_identity());
+        assert!(p1.add(p1.negate()).is_identity());
+        assert_eq!(p1.double().add(p1.clone()),p1.add(p1.double()));
+        assert_eq!(p1.multiply(&BigInt::from(-7)),p1.multiply(&BigInt::from(7)).negate());
+    }
+}
+
+
+#[test]
+fn ladder_matches_affine_multiplication(){
+    let curve = MontgomeryCurve::new(field(3,101),field(5,101)).unwrap();
+
+    for p1 in all_points(&curve,101).iter().skip(1){
+        for k in 0..40{
+            let k = BigInt::from(k);
+            let expected = p1.multiply(&k);
+            let expected = if expected.is_identity() { field(0,101) } else { expected.get_x().clone() };
+            assert_eq!(curve.ladder(&k,p1.get_x(),8),expected);
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use num_bigint::BigInt;
+use num_traits::{Num, One, Signed, Zero};
+
+
+// Integer helpers shared by point counting and curve analysis.
+
+
+// Pollard rho steps the curve analyses spend on each composite before
+// reporting it as unfactored, enough for prime factors up to about 2^32
+pub const RHO_BUDGET: u64 = 1 << 16;
+
+
+// hexadecimal curve constant, panics on malformed input so it is meant for
+// literals only
+pub fn hex(s:&str) -> BigInt{
+    BigInt::from_str_radix(s,16).unwrap()
+}
+
+
+pub fn gcd(a:&BigInt, b:&BigInt) -> BigInt{
+    let (mut a,mut b) = (a.abs(),b.abs());
+    while !b.is_zero(){
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+
+pub fn lcm(a:&BigInt, b:&BigInt) -> BigInt{
+    if a.is_zero() || b.is_zero(){
+        return BigInt::zero();
+    }
+    (a / gcd(a,b) * b).abs()
+}
+
+
+// Jacobi symbol (a / n) for odd n, equal to the Legendre symbol when n is prime
+pub fn jacobi(a:u64, n:u64) -> i32{
+    let (mut a,mut n) = (a % n,n);
+    let mut t = 1;
+    while a != 0{
+        while a % 2 == 0{
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5{
+                t = -t;
+            }
+        }
+        std::mem::swap(&mut a,&mut n);
+        if a % 4 == 3 && n % 4 == 3{
+            t = -t;
+        }
+        a %= n;
+    }
+    if n == 1 { t } else { 0 }
+}
+
+
+const SMALL_PRIMES: [u32;13] = [2,3,5,7,11,13,17,19,23,29,31,37,41];
+
+
+// Miller-Rabin with the first thirteen primes as bases, deterministic below
+// 3.3 * 10^24 and a probable prime test above.
+pub fn is_probable_prime(n:&BigInt) -> bool{
+    if n < &BigInt::from(2){
+        return false;
+    }
+    for &q in SMALL_PRIMES.iter(){
+        let q = BigInt::from(q);
+        if n == &q{
+            return true;
+        }
+        if (n % &q).is_zero(){
+            return false;
+        }
+    }
+    let n_minus_one = n - BigInt::one();
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+
+    'witness: for &base in SMALL_PRIMES.iter(){
+        let mut x = BigInt::from(base).modpow(&d,n);
+        if x.is_one() || x == n_minus_one{
+            continue;
+        }
+        for _ in 1..s{
+            x = &x * &x % n;
+            if x == n_minus_one{
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+
+// Prime factorisation as (prime, exponent) pairs in increasing order. Small
+// factors come from trial division, the rest from Pollard-Brent rho, so this
+// is practical while the second largest prime factor stays below about 2^50.
+pub fn factor(n:&BigInt) -> Vec<(BigInt,u32)>{
+    partial_factor(n,u64::MAX).0
+}
+
+
+// Like `factor`, but Pollard rho gives up on a composite after `budget` steps.
+// Returns the prime factors found and the product of the composites left
+// over, which is 1 exactly when the factorisation is complete.
+pub fn partial_factor(n:&BigInt, budget:u64) -> (Vec<(BigInt,u32)>,BigInt){
+    let mut primes = vec![];
+    let mut rest = n.abs();
+
+    let mut q = 2_u32;
+    while q < 1 << 12 && rest > BigInt::one(){
+        let big_q = BigInt::from(q);
+        while (&rest % &big_q).is_zero(){
+            primes.push(big_q.clone());
+            rest /= &big_q;
+        }
+        q += if q == 2 { 1 } else { 2 };
+    }
+
+    let mut composites = vec![];
+    if rest > BigInt::one(){
+        composites.push(rest);
+    }
+    let mut unfactored = BigInt::one();
+    while let Some(m) = composites.pop(){
+        if is_probable_prime(&m){
+            primes.push(m);
+            continue;
+        }
+        match pollard_brent(&m,budget){
+            Some(d) => {
+                composites.push(&m / &d);
+                composites.push(d);
+            },
+            None => unfactored *= m,
+        }
+    }
+
+    primes.sort();
+    let mut factors: Vec<(BigInt,u32)> = vec![];
+    for prime in primes{
+        match factors.last_mut(){
+            Some((last,e)) if *last == prime => *e += 1,
+            _ => factors.push((prime,1)),
+        }
+    }
+    (factors,unfactored)
+}
+
+
+// a nontrivial factor of the odd composite n, None once `budget` steps are spent
+fn pollard_brent(n:&BigInt, budget:u64) -> Option<BigInt>{
+    let mut c = BigInt::one();
+    let mut steps = 0_u64;
+    loop{
+        let f = |x:&BigInt| (x * x + &c) % n;
+        let (mut y,mut r,mut q) = (BigInt::from(2),1_u64,BigInt::one());
+        let (mut x,mut ys) = (y.clone(),y.clone());
+        let mut g = BigInt::one();
+
+        while g.is_one(){
+            if steps > budget{
+                return None;
+            }
+            steps = steps.saturating_add(r);
+            x = y.clone();
+            for _ in 0..r{
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g.is_one(){
+                ys = y.clone();
+                for _ in 0..(r - k).min(128){
+                    y = f(&y);
+                    q = q * (&x - &y).abs() % n;
+                }
+                g = gcd(&q,n);
+                k += 128;
+            }
+            r *= 2;
+        }
+        if &g == n{
+            // the batched gcd overshot, step through one at a time
+            loop{
+                ys = f(&ys);
+                g = gcd(&(&x - &ys),n);
+                if !g.is_one(){
+                    break;
+                }
+            }
+        }
+        if &g != n{
+            return Some(g);
+        }
+        c += 1;
+    }
+}
+
+
+// splitmix64, the deterministic sampler behind randomized point counting
+// and root finding. Not suitable for anything secret.
+pub struct SplitMix64{
+    state: u64,
+}
+
+impl SplitMix64{
+
+    pub fn new(seed:u64) -> Self{
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64{
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // 128 random bits reduced mod n, close to uniform for n up to about 2^64
+    // and covering every residue of larger n often enough for sampling
+    pub fn below(&mut self, n:&BigInt) -> BigInt{
+        (BigInt::from(self.next_u64()) << 64 | BigInt::from(self.next_u64())) % n
+    }
+
+}
+
+
+
+
+#[test]
+fn gcd_lcm_jacobi(){
+    assert_eq!(gcd(&BigInt::from(84),&BigInt::from(-36)),BigInt::from(12));
+    assert_eq!(lcm(&BigInt::from(4),&BigInt::from(6)),BigInt::from(12));
+
+    // squares mod 11 are 1, 3, 4, 5, 9
+    let residues: Vec<i32> = (0..11).map(|a| jacobi(a,11)).collect();
+    assert_eq!(residues,vec![0,1,-1,1,1,1,-1,-1,-1,1,-1]);
+    assert_eq!(jacobi(2,15),1);
+}
+
+
+#[test]
+fn primality(){
+    let mersenne_61 = (BigInt::one() << 61) - 1;
+    let mersenne_127 = (BigInt::one() << 127) - 1;
+    assert!(is_probable_prime(&mersenne_61));
+    assert!(is_probable_prime(&mersenne_127));
+    assert!(!is_probable_prime(&((BigInt::one() << 67) - 1)));
+    // Carmichael numbers fool the Fermat test but not Miller-Rabin
+    for n in [561,1105,1729,2465,2821,6601]{
+        assert!(!is_probable_prime(&BigInt::from(n)));
+    }
+    assert!(!is_probable_prime(&BigInt::from(1)));
+    assert!(is_probable_prime(&BigInt::from(2)));
+}
+
+
+#[test]
+fn factorisation(){
+    assert_eq!(factor(&BigInt::from(360)),vec![(BigInt::from(2),3),(BigInt::from(3),2),(BigInt::from(5),1)]);
+    assert_eq!(factor(&BigInt::from(1)),vec![]);
+
+    // 2^67 - 1 = 193707721 * 761838257287
+    let cole = (BigInt::one() << 67) - 1;
+    assert_eq!(factor(&cole),vec![(BigInt::from(193707721),1),(BigInt::from(761838257287_u64),1)]);
+
+    let n = BigInt::from(1000003) * BigInt::from(1000003) * BigInt::from(999983) * 12;
+    assert_eq!(factor(&n),vec![(BigInt::from(2),2),(BigInt::from(3),1),(BigInt::from(999983),1),(BigInt::from(1000003),2)]);
+
+    // a budget of 2^14 steps splits off 999983 but not the product
+    // of two 40 bit primes
+    let hard = BigInt::from(1099511627791_u64) * BigInt::from(1099511628401_u64);
+    let (factors,unfactored) = partial_factor(&(&hard * 999983 * 8),1 << 14);
+    assert_eq!(factors,vec![(BigInt::from(2),3),(BigInt::from(999983),1)]);
+    assert_eq!(unfactored,hard);
+    assert_eq!(partial_factor(&cole,u64::MAX),(factor(&cole),BigInt::one()));
+}
@@ -0,0 +1,201 @@
+use crate::elliptic_curve::field_traits::Field;
+#[cfg(test)]
+use num_bigint::BigInt;
+#[cfg(test)]
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::point::point::PointProjective;
+use crate::elliptic_curve::point::jacobian::DoublingFormula;
+use crate::polynomials::polynomial::Polynomial;
+
+
+// Complete addition formulas of Renes, Costello and Batina ("Complete addition
+// formulas for prime order elliptic curves", 2015) in homogeneous projective
+// coordinates. The same code path handles P + Q, P + P, P + O and P + (-P) on
+// curves without points of order two, so there is no branching on the inputs.
+// A curve of even order has such a point and the formulas can then return
+// (0 : 0 : 0), so they are opt-in: check `EllipticCurve::has_odd_order` first.
+// `PointOperations` and the operators use the general formulas instead.
+//
+// With t3 = X1Y2 + X2Y1, t4 = X1Z2 + X2Z1, t5 = Y1Z2 + Y2Z1 and b3 = 3b:
+//   X3 = t3 (Y1Y2 - a t4 - b3 Z1Z2) - t5 (a X1X2 + b3 t4 - a^2 Z1Z2)
+//   Y3 = (3 X1X2 + a Z1Z2)(a X1X2 + b3 t4 - a^2 Z1Z2) + (Y1Y2 + a t4 + b3 Z1Z2)(Y1Y2 - a t4 - b3 Z1Z2)
+//   Z3 = t5 (Y1Y2 + a t4 + b3 Z1Z2) + t3 (3 X1X2 + a Z1Z2)
+impl<F: Field> PointProjective<F>{
+
+    pub fn add_complete(&self, other: &Self) -> Self{
+        match DoublingFormula::for_curve(self.get_a()){
+            DoublingFormula::AZero => self.add_complete_a_zero(other),
+            DoublingFormula::AMinusThree => self.add_complete_a_minus_three(other),
+            DoublingFormula::Generic => self.add_complete_generic(other),
+        }
+    }
+
+    pub fn double_complete(&self) -> Self{
+        self.add_complete(self)
+    }
+
+    // The curve has no point of order two, x^3 + ax + b has no root in the
+    // field, so the complete formulas hold for every pair of points
+    pub fn has_complete_formulas(&self) -> bool{
+        let a = self.get_a();
+        let rhs = Polynomial::new(vec![self.get_b().clone(),a.clone(),a.zero_like(),a.one_like()]);
+        let x = Polynomial::x_like(a);
+        (&x.pow_mod(&a.order(),&rhs) - &x).gcd(&rhs).degree() == Some(0)
+    }
+
+    fn b3(&self) -> F{
+        let b = self.get_b();
+        b.clone() + b + b
+    }
+
+    // t0 = X1X2, t1 = Y1Y2, t2 = Z1Z2 and the three cross terms t3, t4, t5
+    fn complete_products(&self, other: &Self) -> [F;6]{
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+        let (x2,y2,z2) = (other.get_x(),other.get_y(),other.get_z());
+
+        let t0 = x1.clone() * x2;
+        let t1 = y1.clone() * y2;
+        let t2 = z1.clone() * z2;
+        let t3 = (x1.clone() + y1) * (x2.clone() + y2) - &t0 - &t1;
+        let t4 = (x1.clone() + z1) * (x2.clone() + z2) - &t0 - &t2;
+        let t5 = (y1.clone() + z1) * (y2.clone() + z2) - &t1 - &t2;
+        [t0,t1,t2,t3,t4,t5]
+    }
+
+    fn complete_output(&self, t3:F, t5:F, minus:F, plus:F, v:F, w:F) -> Self{
+        let x3 = t3.clone() * &minus - t5.clone() * &w;
+        let y3 = minus * plus.clone() + v.clone() * w;
+        let z3 = t5 * plus + t3 * v;
+
+        PointProjective::new(x3,y3,z3,self.get_a().clone(),self.get_b().clone())
+    }
+
+    // Algorithm 1, 12M + 3m_a + 2m_3b + 23a
+    fn add_complete_generic(&self, other: &Self) -> Self{
+        let a = self.get_a();
+        let b3 = self.b3();
+        let [t0,t1,t2,t3,t4,t5] = self.complete_products(other);
+
+        let u = a.clone() * &t4 + b3.clone() * &t2;
+        let minus = t1.clone() - &u;
+        let plus = t1 + u;
+        let a_t2 = a.clone() * &t2;
+        let v = t0.clone() + &t0 + &t0 + &a_t2;
+        let w = b3 * t4 + a.clone() * (t0 - a_t2);
+
+        self.complete_output(t3,t5,minus,plus,v,w)
+    }
+
+    // Algorithm 4, a = -3: 12M + 2m_b + 29a
+    fn add_complete_a_minus_three(&self, other: &Self) -> Self{
+        let b3 = self.b3();
+        let [t0,t1,t2,t3,t4,t5] = self.complete_products(other);
+
+        // a t4 = -3 t4, a X1X2 - a^2 Z1Z2 = -3 X1X2 - 9 Z1Z2, 3 X1X2 + a Z1Z2 = 3 (X1X2 - Z1Z2)
+        let t4_3 = t4.clone() + &t4 + &t4;
+        let u = b3.clone() * &t2 - t4_3;
+        let minus = t1.clone() - &u;
+        let plus = t1 + u;
+        let d = t0.clone() - &t2;
+        let v = d.clone() + &d + &d;
+        let t2_3 = t2.clone() + &t2 + &t2;
+        let t2_9 = t2_3.clone() + &t2_3 + &t2_3;
+        let w = b3 * t4 - (t0.clone() + &t0 + &t0) - t2_9;
+
+        self.complete_output(t3,t5,minus,plus,v,w)
+    }
+
+    // Algorithm 7, a = 0: 12M + 2m_3b + 19a
+    fn add_complete_a_zero(&self, other: &Self) -> Self{
+        let b3 = self.b3();
+        let [t0,t1,t2,t3,t4,t5] = self.complete_products(other);
+
+        let u = b3.clone() * &t2;
+        let minus = t1.clone() - &u;
+        let plus = t1 + u;
+        let v = t0.clone() + &t0 + &t0;
+        let w = b3 * t4;
+
+        self.complete_output(t3,t5,minus,plus,v,w)
+    }
+
+}
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::point::PointAffine;
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[cfg(test)]
+fn assert_complete_matches_affine(p1: &PointAffine<FieldElement>, order: i32){
+    let identity = PointAffine::identity(p1.get_a().clone(),p1.get_b().clone());
+
+    for i in 0..order{
+        let q = p1.multiply(&BigInt::from(i));
+        let neg_q = p1.multiply(&BigInt::from(-i));
+
+        assert_eq!(p1.to_projective().add_complete(&q.to_projective()).to_affine(),p1.clone() + &q);
+        assert_eq!(q.to_projective().double_complete().to_affine(),q.double());
+        assert_eq!(q.to_projective().add_complete(&neg_q.to_projective()).to_affine(),identity);
+    }
+}
+
+
+#[test]
+fn complete_addition_a_zero(){
+    // y^2 = x^3 + 3 over GF(7) has 13 points
+    let p1 = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    assert_complete_matches_affine(&p1,13);
+}
+
+
+#[test]
+fn complete_addition_a_minus_three(){
+    // y^2 = x^3 - 3x + 15 over GF(103) has prime order 113
+    let p1 = PointAffine::new(field(0,103),field(18,103),field(100,103),field(15,103));
+    assert_eq!(DoublingFormula::for_curve(p1.get_a()),DoublingFormula::AMinusThree);
+    assert_complete_matches_affine(&p1,113);
+}
+
+
+#[test]
+fn complete_addition_generic(){
+    // y^2 = x^3 + 3x + 2 over GF(97) has prime order 103
+    let p1 = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+    assert_eq!(DoublingFormula::for_curve(p1.get_a()),DoublingFormula::Generic);
+    assert_complete_matches_affine(&p1,103);
+}
+
+
+#[test]
+fn general_formulas_on_even_order_curve(){
+    use crate::elliptic_curve::curve::EllipticCurve;
+
+    // y^2 = x^3 + 4x + 6 over GF(11) has 16 points, the complete formulas do
+    // not apply but the default projective arithmetic does
+    let curve = EllipticCurve::new(field(4,11),field(6,11)).unwrap();
+    assert!(!curve.has_odd_order());
+    assert!(EllipticCurve::new(field(3,97),field(2,97)).unwrap().has_odd_order());
+    assert!(!curve.identity().to_projective().has_complete_formulas());
+    assert!(PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97)).to_projective().has_complete_formulas());
+
+    let points: Vec<_> = curve.points().collect();
+    assert_eq!(points.len(),16);
+    for p1 in points.iter(){
+        let projective = p1.to_projective();
+        assert_eq!(projective.double().to_affine(),p1.double());
+        assert_eq!((projective.clone() + projective.clone()).to_affine(),p1.double());
+        for p2 in points.iter(){
+            assert_eq!(projective.add(p2.to_projective()).to_affine(),p1.clone() + p2);
+        }
+        for k in 0..20{
+            assert_eq!(projective.multiply(&BigInt::from(k)).to_affine(),p1.multiply(&BigInt::from(k)));
+        }
+    }
+}
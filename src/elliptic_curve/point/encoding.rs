@@ -0,0 +1,170 @@
+use num_bigint::{BigInt, Sign};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{FieldElementTraits,Field};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+
+
+// SEC 1 v2, section 2.3.3 and 2.3.4: 0x00 for the identity, 0x02 / 0x03 || x
+// for compressed points (the tag carries the parity of y) and 0x04 || x || y
+// for uncompressed points, with coordinates as fixed length big endian.
+pub fn field_length(prime:&BigInt) -> usize{
+    prime.bits().div_ceil(8) as usize
+}
+
+
+fn to_fixed_bytes(n:&BigInt, length:usize) -> Vec<u8>{
+    // to_bytes_be gives [0] for zero, which would leave the output a byte short
+    let bytes = if n.sign() == Sign::NoSign { vec![] } else { n.to_bytes_be().1 };
+    let mut out = vec![0_u8; length.saturating_sub(bytes.len())];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+
+impl PointAffine<FieldElement>{
+
+    pub fn to_encoded_point(&self, compressed:bool) -> Vec<u8>{
+        if self.is_identity(){
+            return vec![0x00];
+        }
+        let length = field_length(&self.element_prime());
+        let x = to_fixed_bytes(self.get_x().get_number(),length);
+
+        let mut out = Vec::with_capacity(1 + 2 * length);
+        if compressed{
+            out.push(if self.get_y().get_number().bit(0) {0x03} else {0x02});
+            out.extend_from_slice(&x);
+        }
+        else{
+            out.push(0x04);
+            out.extend_from_slice(&x);
+            out.extend_from_slice(&to_fixed_bytes(self.get_y().get_number(),length));
+        }
+        out
+    }
+
+    pub fn from_encoded_point(bytes:&[u8], curve:&EllipticCurve) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let prime = curve.get_prime();
+        let length = field_length(prime);
+
+        let read = |slice:&[u8]| -> Result<FieldElement,FieldErrors>{
+            let n = BigInt::from_bytes_be(Sign::Plus,slice);
+            if &n >= prime{
+                return Err(FieldErrors::InvalidParams("Encoded coordinate is not reduced modulo p".to_string()));
+            }
+            FieldElement::new(n,prime.clone())
+        };
+
+        match bytes.first(){
+            Some(0x00) if bytes.len() == 1 => Ok(curve.identity()),
+            Some(tag @ (0x02 | 0x03)) if bytes.len() == 1 + length => {
+                let x = read(&bytes[1..])?;
+                let rhs = &x * &x * &x + curve.get_a() * &x + curve.get_b();
+                let mut y = rhs.sqrt()
+                    .ok_or(FieldErrors::PointNotOnCurve("x^3 + ax + b is not a square".to_string()))?;
+                // y = 0 has no odd square root, so 0x03 cannot name it
+                if y.is_zero() && *tag == 0x03{
+                    return Err(FieldErrors::InvalidParams("Compressed encoding of y = 0 must use 0x02".to_string()));
+                }
+                if y.get_number().bit(0) != (*tag == 0x03){
+                    y = FieldElement::zero(prime.clone()) - y;
+                }
+                Ok(PointAffine::new(x,y,curve.get_a().clone(),curve.get_b().clone()))
+            }
+            Some(0x04) if bytes.len() == 1 + 2 * length => {
+                let x = read(&bytes[1..1 + length])?;
+                let y = read(&bytes[1 + length..])?;
+                let point = PointAffine::new(x,y,curve.get_a().clone(),curve.get_b().clone());
+                if !point.is_on_curve(){
+                    return Err(FieldErrors::PointNotOnCurve("Decoded point is not on the curve".to_string()));
+                }
+                Ok(point)
+            }
+            _ => Err(FieldErrors::InvalidParams("Invalid SEC1 point encoding".to_string())),
+        }
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use num_traits::{Num, Zero};
+#[cfg(test)]
+use crate::elliptic_curve::curves::{secp256k1,secp256r1,p521};
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[test]
+fn sec1_round_trip(){
+    for curve in [secp256k1(),secp256r1(),p521()]{
+        let g = curve.get_generator().unwrap();
+        for k in [1,2,3,1000]{
+            let point = g.to_jacobian().multiply(&BigInt::from(k)).to_affine();
+            for compressed in [true,false]{
+                let bytes = point.to_encoded_point(compressed);
+                assert_eq!(PointAffine::from_encoded_point(&bytes,&curve).unwrap(),point);
+            }
+        }
+        let identity = curve.identity().to_encoded_point(true);
+        assert_eq!(identity,vec![0x00]);
+        assert!(PointAffine::from_encoded_point(&identity,&curve).unwrap().is_identity());
+    }
+}
+
+
+#[test]
+fn sec1_known_encoding(){
+    let curve = secp256k1();
+    let g = curve.get_generator().unwrap();
+    let compressed = g.to_encoded_point(true);
+    let expected = BigInt::from_str_radix("0279BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798",16).unwrap();
+    assert_eq!(compressed,expected.to_bytes_be().1);
+    assert_eq!(g.to_encoded_point(false).len(),65);
+}
+
+
+#[test]
+fn sec1_rejects_invalid_points(){
+    let curve = secp256r1();
+    let g = curve.get_generator().unwrap();
+
+    let mut bytes = g.to_encoded_point(false);
+    bytes[64] ^= 1;
+    assert!(matches!(PointAffine::from_encoded_point(&bytes,&curve),Err(FieldErrors::PointNotOnCurve(_))));
+
+    // half of all x have no point, the first one found must be rejected
+    let mut x = 0_u32;
+    let mut rejected = false;
+    while !rejected{
+        let mut compressed = vec![0x02];
+        compressed.extend_from_slice(&to_fixed_bytes(&BigInt::from(x),32));
+        rejected = matches!(PointAffine::from_encoded_point(&compressed,&curve),Err(FieldErrors::PointNotOnCurve(_)));
+        x += 1;
+    }
+
+    assert!(PointAffine::from_encoded_point(&[0x05;33],&curve).is_err());
+    assert!(PointAffine::from_encoded_point(&bytes[..40],&curve).is_err());
+    assert!(PointAffine::from_encoded_point(&[],&curve).is_err());
+}
+
+
+#[test]
+fn sec1_origin_on_b_zero_curve(){
+    // y^2 = x^3 + x over GF(11): (0, 0) is a curve point, distinct from the identity
+    let p = BigInt::from(11);
+    let curve = EllipticCurve::new(FieldElement::one(p.clone()),FieldElement::zero(p.clone())).unwrap();
+    let origin = curve.point(BigInt::zero(),BigInt::zero()).unwrap();
+    for compressed in [true,false]{
+        let decoded = PointAffine::from_encoded_point(&origin.to_encoded_point(compressed),&curve).unwrap();
+        assert_eq!(decoded,origin);
+        assert!(!decoded.is_identity());
+    }
+    let mut odd = origin.to_encoded_point(true);
+    odd[0] = 0x03;
+    assert!(matches!(PointAffine::from_encoded_point(&odd,&curve),Err(FieldErrors::InvalidParams(_))));
+}
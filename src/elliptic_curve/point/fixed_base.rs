@@ -0,0 +1,387 @@
+use num_bigint::{BigInt, Sign};
+use num_traits::Signed;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::jacobian::PointJacobian;
+use crate::elliptic_curve::scalar::Scalar;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+const MAGIC: &[u8;4] = b"NEFB";
+
+
+// Signed-digit windowed table for a fixed base point G. Row i holds
+// j * 2^(w*i) * G for j = 1..=2^(w-1), so a scalar below 2^bits is multiplied
+// with one table addition per window and no doublings. The table is plain data
+// and can be shared between threads by reference.
+#[derive(Debug,Clone,PartialEq)]
+pub struct FixedBaseTable{
+    window: usize,
+    bits: u64,
+    rows: Vec<Vec<PointAffine<FieldElement>>>,
+}
+
+
+impl FixedBaseTable{
+
+    pub fn new(base:&PointAffine<FieldElement>, bits:u64, window:usize) -> Result<FixedBaseTable,FieldErrors>{
+        if !(2..=16).contains(&window){
+            return Err(FieldErrors::InvalidParams("Window size must be between 2 and 16".to_string()));
+        }
+        if bits == 0{
+            return Err(FieldErrors::InvalidParams("Scalar bit length must be positive".to_string()));
+        }
+        if !base.is_on_curve(){
+            return Err(FieldErrors::PointNotOnCurve("Base point is not on the curve".to_string()));
+        }
+
+        // one extra window absorbs the carry of the signed recoding
+        let windows = (bits as usize + 1).div_ceil(window);
+        let columns = 1_usize << (window - 1);
+
+        let mut rows = Vec::with_capacity(windows);
+        let mut row_base = base.to_jacobian();
+        for _ in 0..windows{
+            let mut row = Vec::with_capacity(columns);
+            let mut acc = row_base.clone();
+            for _ in 0..columns{
+                row.push(acc.to_affine());
+                acc = acc.add(row_base.clone());
+            }
+            rows.push(row);
+            for _ in 0..window{
+                row_base = row_base.double();
+            }
+        }
+
+        Ok(FixedBaseTable{window,bits,rows})
+    }
+
+    pub fn for_scalar_order(base:&PointAffine<FieldElement>, order:&BigInt, window:usize) -> Result<FixedBaseTable,FieldErrors>{
+        Self::new(base,order.bits(),window)
+    }
+
+    pub fn get_window(&self) -> usize{
+        self.window
+    }
+
+    pub fn get_bits(&self) -> u64{
+        self.bits
+    }
+
+    pub fn get_base(&self) -> &PointAffine<FieldElement>{
+        &self.rows[0][0]
+    }
+
+    // Digits d_i in [-2^(w-1), 2^(w-1)] with k = sum d_i 2^(w*i)
+    fn recode(&self, k:&BigInt) -> Vec<i64>{
+        let w = self.window;
+        let half = 1_i64 << (w - 1);
+        let mask = (1_u64 << w) - 1;
+        let digits = k.magnitude().to_u64_digits();
+
+        let mut result = Vec::with_capacity(self.rows.len());
+        let mut carry = 0_i64;
+        for i in 0..self.rows.len(){
+            let mut d = carry;
+            for bit in 0..w{
+                let position = i * w + bit;
+                let limb = digits.get(position / 64).copied().unwrap_or(0);
+                d += (((limb >> (position % 64)) & 1) as i64) << bit;
+            }
+            if d > half{
+                d -= (mask + 1) as i64;
+                carry = 1;
+            }
+            else{
+                carry = 0;
+            }
+            result.push(d);
+        }
+        result
+    }
+
+    // Variable time: the table index and the additions depend on the digits
+    // of k, so the timing and the memory accesses leak the scalar. Only for
+    // public scalars, use `multiply_ladder` for keys and signing nonces.
+    pub fn multiply(&self, k:&BigInt) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        if k.bits() > self.bits{
+            return Err(FieldErrors::InvalidParams(format!("Scalar is longer than the {} bits covered by the table",self.bits)));
+        }
+        let base = self.get_base();
+        let mut r = PointJacobian::identity(base.get_a().clone(),base.get_b().clone());
+
+        for (row,digit) in self.rows.iter().zip(self.recode(k)){
+            if digit > 0{
+                r = r.add(row[digit as usize - 1].to_jacobian());
+            }
+            else if digit < 0{
+                r = r.add(row[(-digit) as usize - 1].negate().to_jacobian());
+            }
+        }
+        let r = r.to_affine();
+
+        if k.is_negative(){
+            Ok(r.negate())
+        }
+        else{
+            Ok(r)
+        }
+    }
+
+    pub fn multiply_scalar(&self, k:&Scalar) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        self.multiply(k.get_number())
+    }
+
+    // Layout: "NEFB", window (1 byte), bits (8 bytes), field element length L
+    // (4 bytes), p, a, b, then every table point as 0x04 || x || y, or 0x00
+    // followed by 2L zero bytes for the identity. All integers are big endian
+    // and every field element takes L bytes.
+    pub fn to_bytes(&self) -> Vec<u8>{
+        let base = self.get_base();
+        let prime = base.element_prime();
+        let length = prime.to_bytes_be().1.len();
+
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(self.window as u8);
+        out.extend_from_slice(&self.bits.to_be_bytes());
+        out.extend_from_slice(&(length as u32).to_be_bytes());
+        write_fixed(&mut out,&prime,length);
+        write_fixed(&mut out,base.get_a().get_number(),length);
+        write_fixed(&mut out,base.get_b().get_number(),length);
+        for row in &self.rows{
+            for point in row{
+                out.push(if point.is_identity() { 0x00 } else { 0x04 });
+                write_fixed(&mut out,point.get_x().get_number(),length);
+                write_fixed(&mut out,point.get_y().get_number(),length);
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes:&[u8]) -> Result<FixedBaseTable,FieldErrors>{
+        let mut reader = Reader{bytes,position:0};
+
+        if reader.take(4)? != MAGIC{
+            return Err(FieldErrors::InvalidParams("Not a fixed base table".to_string()));
+        }
+        let window = reader.take(1)?[0] as usize;
+        let bits = u64::from_be_bytes(reader.take(8)?.try_into().unwrap());
+        let length = u32::from_be_bytes(reader.take(4)?.try_into().unwrap()) as usize;
+        if !(2..=16).contains(&window) || bits == 0 || length == 0{
+            return Err(FieldErrors::InvalidParams("Corrupted fixed base table header".to_string()));
+        }
+
+        // the header fixes the size of the table, check it before allocating
+        let columns = 1_usize << (window - 1);
+        let windows = bits.checked_add(1).and_then(|n| usize::try_from(n.div_ceil(window as u64)).ok());
+        let expected = windows
+            .and_then(|windows| windows.checked_mul(columns))
+            .and_then(|entries| entries.checked_mul(length.checked_mul(2)?.checked_add(1)?))
+            .and_then(|table| table.checked_add(length.checked_mul(3)?)?.checked_add(17));
+        let windows = match (windows,expected){
+            (Some(windows),Some(expected)) if expected == bytes.len() => windows,
+            _ => return Err(FieldErrors::InvalidParams("Fixed base table length does not match its header".to_string())),
+        };
+
+        let prime = BigInt::from_bytes_be(Sign::Plus,reader.take(length)?);
+        let a = FieldElement::new(BigInt::from_bytes_be(Sign::Plus,reader.take(length)?),prime.clone())?;
+        let b = FieldElement::new(BigInt::from_bytes_be(Sign::Plus,reader.take(length)?),prime.clone())?;
+
+        let mut rows = Vec::with_capacity(windows);
+        for _ in 0..windows{
+            let mut row = Vec::with_capacity(columns);
+            for _ in 0..columns{
+                let tag = reader.take(1)?[0];
+                let x = FieldElement::new(BigInt::from_bytes_be(Sign::Plus,reader.take(length)?),prime.clone())?;
+                let y = FieldElement::new(BigInt::from_bytes_be(Sign::Plus,reader.take(length)?),prime.clone())?;
+                let point = match tag{
+                    0x00 if x.get_number().sign() == Sign::NoSign && y.get_number().sign() == Sign::NoSign => PointAffine::identity(a.clone(),b.clone()),
+                    0x04 => PointAffine::new(x,y,a.clone(),b.clone()),
+                    _ => return Err(FieldErrors::InvalidParams("Bad table entry tag".to_string())),
+                };
+                if !point.is_on_curve(){
+                    return Err(FieldErrors::PointNotOnCurve("Table entry is not on the curve".to_string()));
+                }
+                row.push(point);
+            }
+            rows.push(row);
+        }
+
+        // every row must be j * 2^(w*i) * G, which costs as much as building
+        // the table but is the only way a forged row cannot slip through
+        let mut row_base = rows[0][0].to_jacobian();
+        for row in rows.iter(){
+            if !row_matches(row,row_base.clone()){
+                return Err(FieldErrors::Mismatch("Fixed base table rows are not multiples of the base".to_string()));
+            }
+            for _ in 0..window{
+                row_base = row_base.double();
+            }
+        }
+
+        Ok(FixedBaseTable{window,bits,rows})
+    }
+
+}
+
+
+// row[j - 1] = j * row_base for every column j
+fn row_matches(row:&[PointAffine<FieldElement>], row_base:PointJacobian<FieldElement>) -> bool{
+    let mut acc = row_base.clone();
+    for point in row{
+        if point != &acc.to_affine(){
+            return false;
+        }
+        acc = acc.add(row_base.clone());
+    }
+    true
+}
+
+
+fn write_fixed(out:&mut Vec<u8>, n:&BigInt, length:usize){
+    let bytes = n.to_bytes_be().1;
+    let bytes = if n.sign() == Sign::NoSign {Vec::new()} else {bytes};
+    out.extend(std::iter::repeat_n(0_u8,length - bytes.len()));
+    out.extend_from_slice(&bytes);
+}
+
+
+struct Reader<'a>{
+    bytes: &'a [u8],
+    position: usize,
+}
+
+
+impl<'a> Reader<'a>{
+
+    fn take(&mut self, n:usize) -> Result<&'a [u8],FieldErrors>{
+        let end = match self.position.checked_add(n){
+            Some(end) if end <= self.bytes.len() => end,
+            _ => return Err(FieldErrors::InvalidParams("Truncated fixed base table".to_string())),
+        };
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn fixed_base_matches_double_and_add(){
+    // y^2 = x^3 + 3x + 2 over GF(97) has prime order 103
+    let g = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+
+    for window in 2..6{
+        let table = FixedBaseTable::for_scalar_order(&g,&BigInt::from(103),window).unwrap();
+        for k in -127..128{
+            let k = BigInt::from(k);
+            assert_eq!(table.multiply(&k).unwrap(),g.multiply(&k));
+        }
+        assert!(table.multiply(&BigInt::from(1000)).is_err());
+    }
+}
+
+
+#[test]
+fn fixed_base_shared_between_threads(){
+    let g = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+    let order = BigInt::from(103);
+    let table = FixedBaseTable::for_scalar_order(&g,&order,4).unwrap();
+
+    std::thread::scope(|s|{
+        for t in 0..4{
+            let (table,g,order) = (&table,&g,&order);
+            s.spawn(move ||{
+                for k in (t * 25)..(t * 25 + 25){
+                    let k = Scalar::new(BigInt::from(k),order.clone()).unwrap();
+                    assert_eq!(table.multiply_scalar(&k).unwrap(),&k * g);
+                }
+            });
+        }
+    });
+}
+
+
+#[test]
+fn fixed_base_serialization(){
+    let g = PointAffine::new(field(1,103),field(42,103),field(100,103),field(15,103));
+    let table = FixedBaseTable::new(&g,7,3).unwrap();
+
+    let bytes = table.to_bytes();
+    let restored = FixedBaseTable::from_bytes(&bytes).unwrap();
+    assert_eq!(restored,table);
+    assert_eq!(restored.multiply(&BigInt::from(77)).unwrap(),g.multiply(&BigInt::from(77)));
+
+    assert!(FixedBaseTable::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    let mut corrupted = bytes.clone();
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 1;
+    assert!(FixedBaseTable::from_bytes(&corrupted).is_err());
+}
+
+
+#[test]
+fn fixed_base_serializes_the_identity(){
+    // (0, 0) has order two on y^2 = x^3 + x, so [2^i] of it is the identity
+    let origin = PointAffine::new(field(0,11),field(0,11),field(1,11),field(0,11));
+    let table = FixedBaseTable::new(&origin,4,2).unwrap();
+    let restored = FixedBaseTable::from_bytes(&table.to_bytes()).unwrap();
+    assert_eq!(restored,table);
+    for k in 0..8{
+        assert_eq!(restored.multiply(&BigInt::from(k)).unwrap(),origin.multiply(&BigInt::from(k)));
+    }
+}
+
+
+#[test]
+fn fixed_base_rejects_forged_tables(){
+    let g = PointAffine::new(field(1,103),field(42,103),field(100,103),field(15,103));
+    let table = FixedBaseTable::new(&g,7,3).unwrap();
+    let bytes = table.to_bytes();
+
+    // a header claiming 2^64 - 1 bits must be rejected before any allocation
+    let mut huge = bytes[..21].to_vec();
+    huge[5..13].copy_from_slice(&u64::MAX.to_be_bytes());
+    assert!(FixedBaseTable::from_bytes(&huge).is_err());
+    let mut huge = bytes.clone();
+    huge[5..13].copy_from_slice(&(u64::MAX - 1).to_be_bytes());
+    assert!(FixedBaseTable::from_bytes(&huge).is_err());
+
+    // swap two entries of the last row, every point is still on the curve
+    let length = 1;
+    let entry = 1 + 2 * length;
+    let last = bytes.len() - entry;
+    let mut swapped = bytes.clone();
+    let (first,second) = (swapped[last - entry..last].to_vec(),swapped[last..].to_vec());
+    swapped[last - entry..last].copy_from_slice(&second);
+    swapped[last..].copy_from_slice(&first);
+    assert_ne!(swapped,bytes);
+    assert!(matches!(FixedBaseTable::from_bytes(&swapped),Err(FieldErrors::Mismatch(_))));
+
+    // replace a middle row with the multiples of some other point on the curve
+    let other = g.multiply(&BigInt::from(5));
+    let forged_row: Vec<_> = (1..=4).map(|j| other.multiply(&BigInt::from(j))).collect();
+    let mut forged = bytes.clone();
+    let row_start = 17 + 3 * length + 4 * entry;
+    for (j,point) in forged_row.iter().enumerate(){
+        let offset = row_start + j * entry;
+        forged[offset] = if point.is_identity() { 0x00 } else { 0x04 };
+        forged[offset + 1] = point.get_x().get_number().to_bytes_be().1[0];
+        forged[offset + 2] = point.get_y().get_number().to_bytes_be().1[0];
+    }
+    assert_ne!(forged,bytes);
+    assert!(matches!(FixedBaseTable::from_bytes(&forged),Err(FieldErrors::Mismatch(_))));
+}
@@ -0,0 +1,337 @@
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field_traits::Field;
+use crate::elliptic_curve::point::point::{PointAffine,PointProjective};
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Jacobian coordinates: (X:Y:Z) represents the affine point (X/Z^2, Y/Z^3),
+// Z = 0 is the point at infinity.
+#[derive(Debug,Clone,PartialEq)]
+pub struct PointJacobian<F>{
+    values: [F;3],
+    curve: [F;2],
+}
+
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum DoublingFormula{
+    AZero,
+    AMinusThree,
+    Generic,
+}
+
+
+impl DoublingFormula{
+
+    pub fn for_curve<F: Field>(a:&F) -> Self{
+        if a.is_zero(){
+            DoublingFormula::AZero
+        }
+        else if (a.clone() + a.integer_like(3)).is_zero(){
+            DoublingFormula::AMinusThree
+        }
+        else{
+            DoublingFormula::Generic
+        }
+    }
+
+}
+
+
+fn twice<F: Field>(x:&F) -> F{
+    x.clone() + x
+}
+
+
+impl<F: Field> PointJacobian<F>{
+
+    pub fn new(x:F,y:F,z:F,a:F,b:F) -> Self{
+        PointJacobian{values: [x,y,z] , curve: [a,b]}
+    }
+
+    pub fn identity(a:F,b:F) -> Self{
+        PointJacobian{
+            values: [a.one_like(),a.one_like(),a.zero_like()],
+            curve: [a,b]
+        }
+    }
+
+    pub fn get_x(&self) -> &F{
+        &self.values[0]
+    }
+    pub fn get_y(&self) -> &F{
+        &self.values[1]
+    }
+    pub fn get_z(&self) -> &F{
+        &self.values[2]
+    }
+    pub fn get_a(&self) -> &F{
+        &self.curve[0]
+    }
+    pub fn get_b(&self) -> &F{
+        &self.curve[1]
+    }
+
+    pub fn element_prime(&self) -> BigInt{
+        self.values[0].get_prime().clone()
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.get_z().is_zero()
+    }
+
+    pub fn negate(&self) -> Self{
+        PointJacobian{values: [self.get_x().clone(),self.get_y().negate(),self.get_z().clone()],curve: self.curve.clone()}
+    }
+
+    pub fn doubling_formula(&self) -> DoublingFormula{
+        DoublingFormula::for_curve(self.get_a())
+    }
+
+    pub fn to_affine(&self) -> PointAffine<F>{
+        if self.is_identity(){
+            return PointAffine::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let z_inv = self.get_z().modinv();
+        let z_inv2 = z_inv.square();
+        let x = self.get_x().clone() * &z_inv2;
+        let y = self.get_y().clone() * (z_inv2 * z_inv);
+
+        PointAffine::new(x,y,self.get_a().clone(),self.get_b().clone())
+    }
+
+    // (X:Y:Z) jacobian is (XZ : Y : Z^3) in homogeneous projective coordinates
+    pub fn to_projective(&self) -> PointProjective<F>{
+        if self.is_identity(){
+            return PointProjective::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let z = self.get_z();
+        let x = self.get_x().clone() * z;
+        let z3 = z.square() * z;
+
+        PointProjective::new(x,self.get_y().clone(),z3,self.get_a().clone(),self.get_b().clone())
+    }
+
+    pub fn from_projective(point: &PointProjective<F>) -> Self{
+        let z = point.get_z();
+        if z.is_zero(){
+            return Self::identity(point.get_a().clone(),point.get_b().clone());
+        }
+        let x = point.get_x().clone() * z;
+        let y = point.get_y().clone() * &z.square();
+
+        PointJacobian{values: [x,y,z.clone()],curve: [point.get_a().clone(),point.get_b().clone()]}
+    }
+
+    // dbl-2009-l, 2M + 5S
+    fn double_a_zero(&self) -> Self{
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+
+        let a = x1.square();
+        let b = y1.square();
+        let c = b.square();
+        let d = twice(&((x1.clone() + &b).square() - &a - &c));
+        let e = twice(&a) + &a;
+        let f = e.square();
+        let x3 = f - &twice(&d);
+        let c8 = twice(&twice(&twice(&c)));
+        let y3 = e * (d - &x3) - &c8;
+        let z3 = twice(&(y1.clone() * z1));
+
+        PointJacobian{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+    // dbl-2001-b, 3M + 5S
+    fn double_a_minus_three(&self) -> Self{
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+
+        let delta = z1.square();
+        let gamma = y1.square();
+        let beta = x1.clone() * &gamma;
+        let alpha = (x1.clone() - &delta) * (x1.clone() + &delta);
+        let alpha = twice(&alpha) + &alpha;
+        let beta4 = twice(&twice(&beta));
+        let x3 = alpha.square() - &twice(&beta4);
+        let z3 = (y1.clone() + z1).square() - &gamma - &delta;
+        let gamma_sq8 = twice(&twice(&twice(&gamma.square())));
+        let y3 = alpha * (beta4 - &x3) - &gamma_sq8;
+
+        PointJacobian{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+    // dbl-2007-bl, 1M + 8S + 1*a
+    fn double_generic(&self) -> Self{
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+
+        let xx = x1.square();
+        let yy = y1.square();
+        let yyyy = yy.square();
+        let zz = z1.square();
+        let s = twice(&((x1.clone() + &yy).square() - &xx - &yyyy));
+        let m = twice(&xx) + &xx + &(self.get_a().clone() * &zz.square());
+        let x3 = m.square() - &twice(&s);
+        let yyyy8 = twice(&twice(&twice(&yyyy)));
+        let y3 = m * (s - &x3) - &yyyy8;
+        let z3 = (y1.clone() + z1).square() - &yy - &zz;
+
+        PointJacobian{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+}
+
+
+impl<F: Field> PointAffine<F>{
+
+    pub fn to_jacobian(&self) -> PointJacobian<F>{
+        if self.is_identity(){
+            return PointJacobian::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let z = self.get_x().one_like();
+        PointJacobian::new(self.get_x().clone(),self.get_y().clone(),z,self.get_a().clone(),self.get_b().clone())
+    }
+
+}
+
+
+impl<F: Field> PointOperations for PointJacobian<F>{
+
+    // add-2007-bl, 11M + 5S
+    fn add(&self, other: Self) -> Self{
+        if self.is_identity(){
+            return other;
+        }
+        if other.is_identity(){
+            return self.clone();
+        }
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+        let (x2,y2,z2) = (other.get_x(),other.get_y(),other.get_z());
+
+        let z1z1 = z1.square();
+        let z2z2 = z2.square();
+        let u1 = x1.clone() * &z2z2;
+        let u2 = x2.clone() * &z1z1;
+        let s1 = y1.clone() * z2 * &z2z2;
+        let s2 = y2.clone() * z1 * &z1z1;
+        let h = u2 - &u1;
+        let r = s2 - &s1;
+
+        if h.is_zero(){
+            if r.is_zero(){
+                return self.double();
+            }
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+
+        let i = twice(&h).square();
+        let j = h.clone() * &i;
+        let r = twice(&r);
+        let v = u1 * i;
+        let x3 = r.square() - &j - &twice(&v);
+        let y3 = r * (v - &x3) - &twice(&(s1 * j));
+        let z3 = ((z1.clone() + z2).square() - z1z1 - z2z2) * h;
+
+        PointJacobian{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+    fn double(&self) -> Self{
+        if self.is_identity() || self.get_y().is_zero(){
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        match self.doubling_formula(){
+            DoublingFormula::AZero => self.double_a_zero(),
+            DoublingFormula::AMinusThree => self.double_a_minus_three(),
+            DoublingFormula::Generic => self.double_generic(),
+        }
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
+        }
+        let mut q = self.clone();
+        let mut r = Self::identity(self.get_a().clone(),self.get_b().clone());
+
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r = r.add(q.clone());
+            }
+            q = q.double();
+        }
+        r
+    }
+
+}
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn jacobian_double_a_zero(){
+
+    let p1 = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    let p2 = PointAffine::new(field(2,7),field(5,7),field(0,7),field(3,7));
+
+    let j1 = p1.to_jacobian();
+    assert_eq!(j1.doubling_formula(),DoublingFormula::AZero);
+    assert_eq!(j1.double().to_affine(),p2);
+
+}
+
+
+#[test]
+fn jacobian_double_a_minus_three(){
+
+    let p1 = PointAffine::new(field(1,97),field(10,97),field(94,97),field(5,97));
+
+    let j1 = p1.to_jacobian();
+    assert_eq!(j1.doubling_formula(),DoublingFormula::AMinusThree);
+    assert_eq!(j1.double().to_affine(),p1.double());
+
+    let j4 = j1.double().double();
+    assert_eq!(j4.to_affine(),p1.double().double());
+
+}
+
+
+#[test]
+fn jacobian_add_and_multiply(){
+
+    let p1 = PointAffine::new(field(0,97),field(10,97),field(2,97),field(3,97));
+    let p2 = PointAffine::new(field(3,97),field(6,97),field(2,97),field(3,97));
+
+    let j1 = p1.to_jacobian();
+    let j2 = p2.to_jacobian();
+    assert_eq!(j1.doubling_formula(),DoublingFormula::Generic);
+
+    assert_eq!(j1.add(j2).to_affine(),p1.clone() + p2);
+    assert_eq!(j1.add(j1.clone()).to_affine(),p1.double());
+
+    for n in -20..20{
+        let n = BigInt::from(n);
+        assert_eq!(j1.multiply(&n).to_affine(),p1.multiply(&n));
+    }
+
+}
+
+
+#[test]
+fn jacobian_conversions(){
+
+    let p1 = PointAffine::new(field(1,97),field(10,97),field(94,97),field(5,97));
+    let j1 = p1.to_jacobian().double();
+
+    let projective = j1.to_projective();
+    assert_eq!(PointJacobian::from_projective(&projective).to_affine(),j1.to_affine());
+    assert_eq!(projective.normalize_z().get_x(),j1.to_affine().get_x());
+    assert_eq!(projective.normalize_z().get_y(),j1.to_affine().get_y());
+
+    let identity = PointJacobian::identity(field(94,97),field(5,97));
+    assert!(identity.to_affine().is_identity());
+    assert!(PointJacobian::from_projective(&identity.to_projective()).is_identity());
+
+}
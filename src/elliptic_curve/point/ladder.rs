@@ -0,0 +1,124 @@
+use num_bigint::{BigInt, BigUint};
+use crate::elliptic_curve::field_traits::Field;
+use crate::elliptic_curve::point::point::{PointAffine,PointProjective};
+use crate::elliptic_curve::scalar::Scalar;
+use crate::elliptic_curve::errors::FieldErrors;
+
+
+// Montgomery ladder over projective coordinates. Every iteration performs one
+// complete addition, one complete doubling and two conditional swaps, and the
+// number of iterations depends only on the requested bit length, never on the
+// value of the scalar. The complete formulas require a curve without points of
+// order two, which holds for the prime order curves this is meant for, and
+// curves of even order are rejected.
+impl<F: Field> PointProjective<F>{
+
+    // k must be non-negative and fit in `bits` bits, higher bits are ignored.
+    // Reduce negative scalars mod the group order first, or use a `Scalar`.
+    pub fn multiply_ladder_bits(&self, k:&BigInt, bits:u64) -> Result<Self,FieldErrors>{
+        let Some(k) = k.to_biguint() else {
+            return Err(FieldErrors::InvalidParams("The ladder takes non-negative scalars".to_string()));
+        };
+        self.check_ladder_curve()?;
+        Ok(self.ladder(&k,bits))
+    }
+
+    pub fn multiply_ladder(&self, k:&Scalar) -> Result<Self,FieldErrors>{
+        self.check_ladder_curve()?;
+        Ok(self.ladder(k.get_number().magnitude(),k.bit_length()))
+    }
+
+    fn check_ladder_curve(&self) -> Result<(),FieldErrors>{
+        if !self.has_complete_formulas(){
+            return Err(FieldErrors::InvalidParams("The ladder needs a curve of odd order".to_string()));
+        }
+        Ok(())
+    }
+
+    fn ladder(&self, k:&BigUint, bits:u64) -> Self{
+        let mut r0 = Self::identity(self.get_a().clone(),self.get_b().clone());
+        let mut r1 = self.clone();
+
+        for i in (0..bits).rev(){
+            let bit = k.bit(i) as u8;
+            r0.conditional_swap(&mut r1,bit);
+            r1 = r0.add_complete(&r1);
+            r0 = r0.double_complete();
+            r0.conditional_swap(&mut r1,bit);
+        }
+        r0
+    }
+
+}
+
+
+impl<F: Field> PointAffine<F>{
+
+    pub fn multiply_ladder(&self, k:&Scalar) -> Result<Self,FieldErrors>{
+        Ok(self.to_projective().multiply_ladder(k)?.to_affine())
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn ladder_matches_double_and_add(){
+    // y^2 = x^3 - 3x + 15 over GF(103) has prime order 113
+    let p1 = PointAffine::new(field(0,103),field(18,103),field(100,103),field(15,103));
+    let order = BigInt::from(113);
+
+    for k in -5..130{
+        let scalar = Scalar::new(BigInt::from(k),order.clone()).unwrap();
+        assert_eq!(p1.multiply_ladder(&scalar).unwrap(),p1.multiply(&BigInt::from(k)));
+    }
+}
+
+
+#[test]
+fn ladder_fixed_bit_length(){
+    // y^2 = x^3 + 3 over GF(7) has 13 points
+    let p1 = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    let projective = p1.to_projective();
+
+    // leading zero bits only add iterations, they do not change the result
+    for k in 0..13{
+        let k = BigInt::from(k);
+        let short = projective.multiply_ladder_bits(&k,4).unwrap().to_affine();
+        let long = projective.multiply_ladder_bits(&k,64).unwrap().to_affine();
+        assert_eq!(short,long);
+        assert_eq!(short,p1.multiply(&k));
+    }
+}
+
+
+#[test]
+fn ladder_rejects_negative_scalars(){
+    let p1 = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    let projective = p1.to_projective();
+    assert!(projective.multiply_ladder_bits(&BigInt::from(-2),4).is_err());
+
+    // a Scalar reduces -2 to 11 mod 13, which is [-2]P
+    let scalar = Scalar::new(BigInt::from(-2),BigInt::from(13)).unwrap();
+    assert_eq!(p1.multiply_ladder(&scalar).unwrap(),p1.multiply(&BigInt::from(-2)));
+}
+
+
+#[test]
+fn ladder_rejects_even_order_curves(){
+    // y^2 = x^3 + 4x + 6 over GF(11) has 16 points
+    let p1 = PointAffine::new(field(3,11),field(1,11),field(4,11),field(6,11));
+    assert!(p1.is_on_curve());
+    let scalar = Scalar::new(BigInt::from(3),BigInt::from(16)).unwrap();
+    assert!(p1.multiply_ladder(&scalar).is_err());
+    assert!(p1.to_projective().multiply_ladder_bits(&BigInt::from(3),4).is_err());
+}
@@ -1,2 +1,14 @@
+#[allow(clippy::module_inception)]
 pub mod point;
-pub mod pointTraits;
\ No newline at end of file
+#[allow(non_snake_case)]
+pub mod pointTraits;
+pub mod jacobian;
+pub mod complete;
+pub mod ladder;
+pub mod wnaf;
+pub mod fixed_base;
+pub mod msm;
+pub mod ops;
+pub mod encoding;
+#[cfg(feature = "parallel")]
+pub mod parallel;
@@ -0,0 +1,198 @@
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::jacobian::PointJacobian;
+use crate::elliptic_curve::point::wnaf::{wnaf,odd_multiples};
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Below this many terms interleaved wNAF (Straus / Shamir's trick) is faster
+// than bucketing.
+pub const PIPPENGER_THRESHOLD: usize = 32;
+
+const STRAUS_WINDOW: usize = 4;
+
+
+// Computes sum k_i * P_i. All intermediate work is done in Jacobian
+// coordinates, the result is normalised once at the end.
+pub fn msm(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<PointAffine<FieldElement>,FieldErrors>{
+    check_inputs(scalars,points)?;
+    if points.len() < PIPPENGER_THRESHOLD{
+        Ok(msm_straus(scalars,points)?.to_affine())
+    }
+    else{
+        Ok(msm_pippenger(scalars,points)?.to_affine())
+    }
+}
+
+
+pub(crate) fn check_inputs(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<(),FieldErrors>{
+    if scalars.len() != points.len(){
+        return Err(FieldErrors::Mismatch(format!("{} scalars for {} points",scalars.len(),points.len())));
+    }
+    if points.is_empty(){
+        return Err(FieldErrors::InvalidParams("Multi-scalar multiplication needs at least one point".to_string()));
+    }
+    let curve = (points[0].get_a(),points[0].get_b());
+    if points.iter().any(|p| (p.get_a(),p.get_b()) != curve){
+        return Err(FieldErrors::Mismatch("Points are not on the same curve".to_string()));
+    }
+    Ok(())
+}
+
+
+pub fn msm_straus(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<PointJacobian<FieldElement>,FieldErrors>{
+    check_inputs(scalars,points)?;
+
+    let digits: Vec<Vec<i64>> = scalars.iter().map(|k| wnaf(k,STRAUS_WINDOW)).collect::<Result<_,_>>()?;
+    let tables: Vec<Vec<PointJacobian<FieldElement>>> = points.iter()
+        .map(|p| odd_multiples(&p.to_jacobian(),STRAUS_WINDOW))
+        .collect::<Result<_,_>>()?;
+    let length = digits.iter().map(|d| d.len()).max().unwrap_or(0);
+
+    let mut r = PointJacobian::identity(points[0].get_a().clone(),points[0].get_b().clone());
+    for i in (0..length).rev(){
+        r = r.double();
+        for (d,table) in digits.iter().zip(tables.iter()){
+            let digit = d.get(i).copied().unwrap_or(0);
+            if digit > 0{
+                r = r.add(table[(digit as usize) / 2].clone());
+            }
+            else if digit < 0{
+                r = r.add(table[(-digit as usize) / 2].negate());
+            }
+        }
+    }
+    Ok(r)
+}
+
+
+// Window size c for Pippenger's method, roughly log2(n) - 2
+pub fn pippenger_window(n:usize) -> usize{
+    let log = usize::BITS - n.leading_zeros();
+    (log as usize).saturating_sub(2).max(2)
+}
+
+
+// Scalars are made non-negative by negating their points.
+pub(crate) fn normalise_signs(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> (Vec<BigInt>,Vec<PointJacobian<FieldElement>>){
+    scalars.iter().zip(points.iter())
+        .map(|(k,p)| {
+            if k.is_negative(){
+                (-k,p.negate().to_jacobian())
+            }
+            else{
+                (k.clone(),p.to_jacobian())
+            }
+        })
+        .unzip()
+}
+
+
+// Sum of the points whose scalars have window `index` equal to j, weighted by j,
+// computed with 2^c - 1 buckets and a running sum.
+pub(crate) fn pippenger_window_sum(scalars:&[BigInt], points:&[PointJacobian<FieldElement>], c:usize, index:usize) -> PointJacobian<FieldElement>{
+    let identity = PointJacobian::identity(points[0].get_a().clone(),points[0].get_b().clone());
+    let mut buckets = vec![identity.clone(); (1 << c) - 1];
+
+    for (k,p) in scalars.iter().zip(points.iter()){
+        let mut digit = 0_usize;
+        for bit in 0..c{
+            if k.magnitude().bit((index * c + bit) as u64){
+                digit |= 1 << bit;
+            }
+        }
+        if digit != 0{
+            buckets[digit - 1] = buckets[digit - 1].add(p.clone());
+        }
+    }
+
+    let mut running = identity.clone();
+    let mut sum = identity;
+    for bucket in buckets.into_iter().rev(){
+        running = running.add(bucket);
+        sum = sum.add(running.clone());
+    }
+    sum
+}
+
+
+pub(crate) fn combine_windows(window_sums:Vec<PointJacobian<FieldElement>>, c:usize) -> PointJacobian<FieldElement>{
+    let mut iter = window_sums.into_iter().rev();
+    let mut r = iter.next().unwrap();
+    for w in iter{
+        for _ in 0..c{
+            r = r.double();
+        }
+        r = r.add(w);
+    }
+    r
+}
+
+
+pub fn msm_pippenger(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<PointJacobian<FieldElement>,FieldErrors>{
+    check_inputs(scalars,points)?;
+
+    let (scalars,points) = normalise_signs(scalars,points);
+    let c = pippenger_window(points.len());
+    let bits = scalars.iter().map(|k| k.bits()).max().unwrap_or(0) as usize;
+    let windows = bits.div_ceil(c).max(1);
+
+    let window_sums = (0..windows)
+        .map(|j| pippenger_window_sum(&scalars,&points,c,j))
+        .collect();
+    Ok(combine_windows(window_sums,c))
+}
+
+
+
+pub fn batch_multiply(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<Vec<PointAffine<FieldElement>>,FieldErrors>{
+    if scalars.len() != points.len(){
+        return Err(FieldErrors::Mismatch(format!("{} scalars for {} points",scalars.len(),points.len())));
+    }
+    Ok(scalars.iter().zip(points.iter())
+        .map(|(k,p)| p.multiply(k))
+        .collect())
+}
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::{field, msm_inputs};
+
+
+#[cfg(test)]
+fn naive_msm(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> PointAffine<FieldElement>{
+    let mut r = PointAffine::identity(points[0].get_a().clone(),points[0].get_b().clone());
+    for (k,p) in scalars.iter().zip(points.iter()){
+        r += &p.multiply(k);
+    }
+    r
+}
+
+
+#[test]
+fn msm_straus_and_pippenger_agree(){
+    for n in [1,2,5,31,32,70]{
+        let (scalars,points) = msm_inputs(n);
+        let expected = naive_msm(&scalars,&points);
+
+        assert_eq!(msm_straus(&scalars,&points).unwrap().to_affine(),expected);
+        assert_eq!(msm_pippenger(&scalars,&points).unwrap().to_affine(),expected);
+        assert_eq!(msm(&scalars,&points).unwrap(),expected);
+    }
+}
+
+
+#[test]
+fn msm_rejects_bad_inputs(){
+    let (scalars,points) = msm_inputs(3);
+    assert!(matches!(msm(&scalars[..2],&points),Err(FieldErrors::Mismatch(_))));
+    assert!(msm(&[],&[]).is_err());
+
+    let other = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    assert!(msm(&scalars[..2],&[points[0].clone(),other]).is_err());
+}
@@ -0,0 +1,334 @@
+use num_bigint::BigInt;
+use crate::elliptic_curve::field_traits::Field;
+use crate::elliptic_curve::point::point::{PointAffine,PointProjective};
+use crate::elliptic_curve::point::jacobian::PointJacobian;
+use crate::elliptic_curve::edwards::{EdwardsPoint,EdwardsExtended};
+#[cfg(test)]
+use crate::elliptic_curve::edwards::EdwardsCurve;
+#[cfg(test)]
+use crate::elliptic_curve::curve::EllipticCurve;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+use std::ops::{Add,Sub,Mul,Neg,AddAssign,SubAssign};
+use std::iter::Sum;
+
+
+// Operator impls for every coordinate system and curve model. Each type only
+// provides addition and negation through `GroupLaw`, the operators below are
+// all derived from it. A point only knows the identity of its curve once it
+// has a point to copy it from, so `Sum` collects into an `Option` that is None
+// for no points, and `sum_points` takes the identity explicitly.
+pub trait GroupLaw: Sized{
+    fn group_add(&self, other:&Self) -> Self;
+    fn group_neg(&self) -> Self;
+}
+
+
+// identity + P1 + P2 + ..., the identity for no points
+pub fn sum_points<T: GroupLaw>(identity:T, points:impl IntoIterator<Item = T>) -> T{
+    points.into_iter().fold(identity,|acc,point| acc.group_add(&point))
+}
+
+
+impl<F: Field> GroupLaw for PointAffine<F>{
+    fn group_add(&self, other:&Self) -> Self{
+        self.clone() + other
+    }
+    fn group_neg(&self) -> Self{
+        self.negate()
+    }
+}
+
+
+impl<F: Field> GroupLaw for PointProjective<F>{
+    fn group_add(&self, other:&Self) -> Self{
+        PointOperations::add(self,other.clone())
+    }
+    fn group_neg(&self) -> Self{
+        self.negate()
+    }
+}
+
+
+impl<F: Field> GroupLaw for PointJacobian<F>{
+    fn group_add(&self, other:&Self) -> Self{
+        PointOperations::add(self,other.clone())
+    }
+    fn group_neg(&self) -> Self{
+        self.negate()
+    }
+}
+
+
+impl<F: Field> GroupLaw for EdwardsPoint<F>{
+    fn group_add(&self, other:&Self) -> Self{
+        PointOperations::add(self,other.clone())
+    }
+    fn group_neg(&self) -> Self{
+        self.negate()
+    }
+}
+
+
+impl<F: Field> GroupLaw for EdwardsExtended<F>{
+    fn group_add(&self, other:&Self) -> Self{
+        PointOperations::add(self,other.clone())
+    }
+    fn group_neg(&self) -> Self{
+        self.negate()
+    }
+}
+
+
+macro_rules! impl_owned_add {
+    ($t:ident) => {
+        impl<F: Field> Add<$t<F>> for $t<F>{
+            type Output = $t<F>;
+            fn add(self, other:$t<F>) -> $t<F>{
+                self.group_add(&other)
+            }
+        }
+
+        impl<F: Field> Add<&$t<F>> for $t<F>{
+            type Output = $t<F>;
+            fn add(self, other:&$t<F>) -> $t<F>{
+                self.group_add(other)
+            }
+        }
+    };
+}
+
+
+macro_rules! impl_ref_add {
+    ($t:ident) => {
+        impl<F: Field> Add<$t<F>> for &$t<F>{
+            type Output = $t<F>;
+            fn add(self, other:$t<F>) -> $t<F>{
+                self.group_add(&other)
+            }
+        }
+
+        impl<F: Field> Add<&$t<F>> for &$t<F>{
+            type Output = $t<F>;
+            fn add(self, other:&$t<F>) -> $t<F>{
+                self.group_add(other)
+            }
+        }
+    };
+}
+
+
+macro_rules! impl_scalar_mul {
+    ($t:ident) => {
+        impl<F: Field> Mul<&BigInt> for &$t<F>{
+            type Output = $t<F>;
+            fn mul(self, n:&BigInt) -> $t<F>{
+                self.multiply(n)
+            }
+        }
+
+        impl<F: Field> Mul<&$t<F>> for &BigInt{
+            type Output = $t<F>;
+            fn mul(self, point:&$t<F>) -> $t<F>{
+                point.multiply(self)
+            }
+        }
+    };
+}
+
+
+macro_rules! impl_group_ops {
+    ($t:ident) => {
+        impl<F: Field> Neg for $t<F>{
+            type Output = $t<F>;
+            fn neg(self) -> $t<F>{
+                self.group_neg()
+            }
+        }
+
+        impl<F: Field> Neg for &$t<F>{
+            type Output = $t<F>;
+            fn neg(self) -> $t<F>{
+                self.group_neg()
+            }
+        }
+
+        impl<F: Field> Sub<$t<F>> for $t<F>{
+            type Output = $t<F>;
+            fn sub(self, other:$t<F>) -> $t<F>{
+                self.group_add(&other.group_neg())
+            }
+        }
+
+        impl<F: Field> Sub<&$t<F>> for $t<F>{
+            type Output = $t<F>;
+            fn sub(self, other:&$t<F>) -> $t<F>{
+                self.group_add(&other.group_neg())
+            }
+        }
+
+        impl<F: Field> Sub<$t<F>> for &$t<F>{
+            type Output = $t<F>;
+            fn sub(self, other:$t<F>) -> $t<F>{
+                self.group_add(&other.group_neg())
+            }
+        }
+
+        impl<F: Field> Sub<&$t<F>> for &$t<F>{
+            type Output = $t<F>;
+            fn sub(self, other:&$t<F>) -> $t<F>{
+                self.group_add(&other.group_neg())
+            }
+        }
+
+        impl<F: Field> AddAssign<$t<F>> for $t<F>{
+            fn add_assign(&mut self, other:$t<F>){
+                *self = self.group_add(&other);
+            }
+        }
+
+        impl<F: Field> AddAssign<&$t<F>> for $t<F>{
+            fn add_assign(&mut self, other:&$t<F>){
+                *self = self.group_add(other);
+            }
+        }
+
+        impl<F: Field> SubAssign<$t<F>> for $t<F>{
+            fn sub_assign(&mut self, other:$t<F>){
+                *self = self.group_add(&other.group_neg());
+            }
+        }
+
+        impl<F: Field> SubAssign<&$t<F>> for $t<F>{
+            fn sub_assign(&mut self, other:&$t<F>){
+                *self = self.group_add(&other.group_neg());
+            }
+        }
+
+        impl<F: Field> Sum<$t<F>> for Option<$t<F>>{
+            fn sum<I: Iterator<Item = $t<F>>>(iter:I) -> Self{
+                iter.reduce(|acc,point| acc.group_add(&point))
+            }
+        }
+
+        impl<'a, F: Field> Sum<&'a $t<F>> for Option<$t<F>>{
+            fn sum<I: Iterator<Item = &'a $t<F>>>(mut iter:I) -> Self{
+                let first = iter.next()?.clone();
+                Some(iter.fold(first,|acc,point| acc.group_add(point)))
+            }
+        }
+    };
+}
+
+
+// owned additions and scalar multiplication of affine points live in point.rs
+impl_ref_add!(PointAffine);
+impl_group_ops!(PointAffine);
+
+impl_owned_add!(PointProjective);
+impl_ref_add!(PointProjective);
+impl_scalar_mul!(PointProjective);
+impl_group_ops!(PointProjective);
+
+impl_owned_add!(PointJacobian);
+impl_ref_add!(PointJacobian);
+impl_scalar_mul!(PointJacobian);
+impl_group_ops!(PointJacobian);
+
+impl_owned_add!(EdwardsPoint);
+impl_ref_add!(EdwardsPoint);
+impl_scalar_mul!(EdwardsPoint);
+impl_group_ops!(EdwardsPoint);
+
+impl_owned_add!(EdwardsExtended);
+impl_ref_add!(EdwardsExtended);
+impl_scalar_mul!(EdwardsExtended);
+impl_group_ops!(EdwardsExtended);
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn affine_operators(){
+    // y^2 = x^3 + 3x + 2 over GF(97) has prime order 103
+    let g = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+    let p = g.multiply(&BigInt::from(5));
+    let q = g.multiply(&BigInt::from(17));
+    let (a,b) = (BigInt::from(11),BigInt::from(4));
+
+    // 11 * 5 - 4 * 17 = -13
+    assert_eq!(&a * &p - &b * &q,g.multiply(&BigInt::from(-13)));
+    assert_eq!(&p + &q,g.multiply(&BigInt::from(22)));
+    assert_eq!(&p - &p,PointAffine::identity(g.get_a().clone(),g.get_b().clone()));
+    assert_eq!(-&p + p.clone(),&p - p.clone());
+    assert_eq!(-(-p.clone()),p);
+
+    let mut r = p.clone();
+    r += &q;
+    r -= q.clone();
+    assert_eq!(r,p);
+
+    let identity = PointAffine::identity(g.get_a().clone(),g.get_b().clone());
+    let points = vec![p.clone(),q.clone(),g.clone()];
+    assert_eq!(sum_points(identity.clone(),points.iter().cloned()),g.multiply(&BigInt::from(23)));
+    assert_eq!(sum_points(identity.clone(),points.clone()),g.multiply(&BigInt::from(23)));
+    assert_eq!(sum_points(identity.clone(),vec![]),identity);
+    assert_eq!(points.iter().sum::<Option<_>>(),Some(g.multiply(&BigInt::from(23))));
+    assert_eq!(points[..0].iter().sum::<Option<_>>(),None);
+    assert_eq!(points.into_iter().sum::<Option<_>>(),Some(g.multiply(&BigInt::from(23))));
+
+    // y^2 = x^3 + 3x + 2 as an EllipticCurve, whose sum knows its identity
+    let curve = EllipticCurve::new(field(3,97),field(2,97)).unwrap();
+    assert_eq!(curve.sum_points(vec![p.clone(),q.clone()]),&p + &q);
+    assert!(curve.sum_points(vec![]).is_identity());
+}
+
+
+#[test]
+fn projective_and_jacobian_operators(){
+    let g = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+    let (a,b) = (BigInt::from(40),BigInt::from(-7));
+
+    let gp = g.to_projective();
+    let pp = &a * &gp - &b * &(&gp + &gp);
+    assert_eq!(pp.to_affine(),g.multiply(&BigInt::from(54)));
+
+    let gj = g.to_jacobian();
+    let mut pj = &gj * &a;
+    pj -= &gj * &b;
+    pj += gj.clone() + gj.clone();
+    assert_eq!(pj.to_affine(),g.multiply(&BigInt::from(49)));
+
+    let identity = PointJacobian::identity(g.get_a().clone(),g.get_b().clone());
+    let sum = sum_points(identity,vec![gj.clone(),-gj.clone(),gj.clone()]);
+    assert_eq!(sum.to_affine(),g);
+    let sum = sum_points(PointProjective::identity(g.get_a().clone(),g.get_b().clone()),[gp.clone(),gp.clone()]);
+    assert_eq!(sum.to_affine(),g.double());
+    let sum: Option<PointJacobian<_>> = [gj.clone(),gj.clone(),gj].iter().sum();
+    assert_eq!(sum.unwrap().to_affine(),g.multiply(&BigInt::from(3)));
+}
+
+
+#[test]
+fn edwards_operators(){
+    let curve = EdwardsCurve::new(field(4,101),field(2,101)).unwrap();
+    let g = (1..101).find_map(|x| (0..101).find_map(|y| curve.point(field(x,101),field(y,101)).ok())).unwrap();
+    let (a,b) = (BigInt::from(9),BigInt::from(4));
+
+    assert_eq!(&a * &g - &b * &g,g.multiply(&BigInt::from(5)));
+    assert_eq!(-&g + &g,curve.identity());
+
+    let ge = g.to_extended();
+    let mut pe = &ge * &a;
+    pe -= &ge * &b;
+    pe += ge.clone();
+    assert_eq!(pe.to_affine(),g.multiply(&BigInt::from(6)));
+
+    assert_eq!(curve.sum_points(vec![g.clone(),g.clone(),-g.clone()]),g);
+    assert_eq!(curve.sum_points(vec![]),curve.identity());
+}
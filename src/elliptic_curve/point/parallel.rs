@@ -0,0 +1,79 @@
+use num_bigint::BigInt;
+use rayon::prelude::*;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::jacobian::PointJacobian;
+use crate::elliptic_curve::point::msm::{check_inputs,normalise_signs,pippenger_window,pippenger_window_sum,combine_windows,msm_straus,PIPPENGER_THRESHOLD};
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Parallel versions of the batch and multi-scalar multiplications. Work is only
+// split across threads, every window and every product is computed exactly as
+// in the serial path and recombined in the same order, so the results are
+// identical.
+pub fn msm_pippenger_parallel(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<PointJacobian<FieldElement>,FieldErrors>{
+    check_inputs(scalars,points)?;
+
+    let (scalars,points) = normalise_signs(scalars,points);
+    let c = pippenger_window(points.len());
+    let bits = scalars.iter().map(|k| k.bits()).max().unwrap_or(0) as usize;
+    let windows = bits.div_ceil(c).max(1);
+
+    let window_sums = (0..windows).into_par_iter()
+        .map(|j| pippenger_window_sum(&scalars,&points,c,j))
+        .collect();
+    Ok(combine_windows(window_sums,c))
+}
+
+
+pub fn msm_parallel(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<PointAffine<FieldElement>,FieldErrors>{
+    check_inputs(scalars,points)?;
+    if points.len() < PIPPENGER_THRESHOLD{
+        Ok(msm_straus(scalars,points)?.to_affine())
+    }
+    else{
+        Ok(msm_pippenger_parallel(scalars,points)?.to_affine())
+    }
+}
+
+
+pub fn batch_multiply_parallel(scalars:&[BigInt], points:&[PointAffine<FieldElement>]) -> Result<Vec<PointAffine<FieldElement>>,FieldErrors>{
+    if scalars.len() != points.len(){
+        return Err(FieldErrors::Mismatch(format!("{} scalars for {} points",scalars.len(),points.len())));
+    }
+    Ok(scalars.par_iter().zip(points.par_iter())
+        .map(|(k,p)| p.multiply(k))
+        .collect())
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::msm::{msm,msm_pippenger,batch_multiply};
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::msm_inputs;
+
+
+#[test]
+fn parallel_msm_matches_serial(){
+    for n in [1,10,40,150]{
+        let (scalars,points) = msm_inputs(n);
+
+        assert_eq!(msm_pippenger_parallel(&scalars,&points).unwrap(),msm_pippenger(&scalars,&points).unwrap());
+        assert_eq!(msm_parallel(&scalars,&points).unwrap(),msm(&scalars,&points).unwrap());
+    }
+}
+
+
+#[test]
+fn parallel_batch_multiply_matches_serial(){
+    let (scalars,points) = msm_inputs(50);
+
+    assert_eq!(batch_multiply_parallel(&scalars,&points).unwrap(),batch_multiply(&scalars,&points).unwrap());
+    assert!(batch_multiply_parallel(&scalars[..3],&points).is_err());
+}
@@ -1,64 +1,67 @@
-use num_bigint::{ToBigInt, BigInt};
-use num_traits::{One, Zero};
-use core::num;
-use crate::elliptic_curve::field_traits::FieldElementTraits;
+use num_bigint::BigInt;
+use num_traits::Signed;
+use crate::elliptic_curve::field_traits::Field;
+#[cfg(test)]
 use crate::elliptic_curve::field::FieldElement;
 
 use crate::elliptic_curve::point::pointTraits::PointOperations;
 
-use std::ops::Add;
+use std::ops::{Add,Mul};
 
 
+// The point at infinity has no affine coordinates, so it is carried as a flag
+// (with zero coordinates) rather than as the pair (0, 0), which is an ordinary
+// point of order two on curves with b = 0.
 #[derive(Debug,Clone,PartialEq,Copy)]
-pub struct PointAffine<FieldElement>{
-    values: [FieldElement;2],
-    curve: [FieldElement;2],
-     
+pub struct PointAffine<F>{
+    values: [F;2],
+    curve: [F;2],
+    infinity: bool,
 }
 
 
 #[derive(Debug,Clone,PartialEq,Copy)]
-pub struct PointProjective<FieldElement>{
-    values: [FieldElement;3],
-    curve: [FieldElement;2],
+pub struct PointProjective<F>{
+    values: [F;3],
+    curve: [F;2],
 
 }
 
 
-impl PointAffine<FieldElement>{
+impl<F: Field> PointAffine<F>{
 
-    pub fn new(x:FieldElement,y:FieldElement,a:FieldElement,b:FieldElement) -> Self{
-        PointAffine{values: [x,y],curve: [a,b]}
+    pub fn new(x:F,y:F,a:F,b:F) -> Self{
+        PointAffine{values: [x,y],curve: [a,b],infinity: false}
     }
 
-    pub fn get_x(&self) -> &FieldElement{
+    pub fn get_x(&self) -> &F{
         &self.values[0]
         
     }
-    pub fn get_y(&self) -> &FieldElement{
+    pub fn get_y(&self) -> &F{
         &self.values[1]
         
     }
-    pub fn get_values(&self) -> &[FieldElement;2]{
+    pub fn get_values(&self) -> &[F;2]{
         &self.values
     }
 
-    pub fn get_a(&self) -> &FieldElement{
+    pub fn get_a(&self) -> &F{
         &self.curve[0]
         
     }
-    pub fn get_b(&self) -> &FieldElement{
+    pub fn get_b(&self) -> &F{
         &self.curve[1]
         
     }
     
-    pub fn to_projectivez<>(&self, z: FieldElement, a: FieldElement, b:FieldElement) -> PointProjective<FieldElement>{
+    pub fn to_projectivez<>(&self, z: F, a: F, b:F) -> PointProjective<F>{
 
         let mut x = self.get_x().clone();
         let mut y = self.get_y().clone();
         
-        x = (z.modinv() * x);
-        y = (z.modinv() * y);
+        x = z.modinv() * x;
+        y = z.modinv() * y;
 
         PointProjective {values: [x,y,z], curve: [a,b]}
 
@@ -68,32 +71,88 @@ impl PointAffine<FieldElement>{
         self.values[0].get_prime().clone()
     }
 
+    pub fn identity(a:F,b:F) -> Self{
+        PointAffine{values: [a.zero_like(),a.zero_like()],curve: [a,b],infinity: true}
+    }
+
+    pub fn is_identity(&self) -> bool{
+        self.infinity
+    }
+
+    pub fn is_on_curve(&self) -> bool{
+        if self.is_identity(){
+            return true;
+        }
+        let (x,y) = (self.get_x(),self.get_y());
+        y.square() == x.square() * x + self.get_a().clone() * x + self.get_b()
+    }
+
+    pub fn negate(&self) -> Self{
+        if self.is_identity(){
+            return self.clone();
+        }
+        PointAffine{values: [self.get_x().clone(),self.get_y().negate()],curve: self.curve.clone(),infinity: false}
+    }
+
+    pub fn to_projective(&self) -> PointProjective<F>{
+        if self.is_identity(){
+            return PointProjective::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let z = self.get_x().one_like();
+        PointProjective{values: [self.get_x().clone(),self.get_y().clone(),z],curve: self.curve.clone()}
+    }
+
+    fn add_points(&self, other:&Self) -> Self{
+        if self.is_identity(){
+            other.clone()
+        }
+        else if other.is_identity(){
+            self.clone()
+        }
+        else if self.get_x() == other.get_x() && self.get_y() == other.get_y() {
+            self.double()
+        }
+        else if self.get_x() == other.get_x() {
+            PointAffine::identity(self.curve[0].clone(),self.curve[1].clone())
+        }
+        else {
+            let slope = (self.get_y().clone() - other.get_y()) / (self.get_x().clone() - other.get_x());
+
+            let x_r = slope.square() - self.get_x() - other.get_x();
+
+            let y_r = slope * (self.get_x().clone() - &x_r) - self.get_y();
+
+            Self{values: [x_r,y_r],curve: [self.curve[0].clone(),self.curve[1].clone()],infinity: false}
+
+        }
+    }
+
 }
 
 
 
-impl PointProjective<FieldElement>{
+impl<F: Field> PointProjective<F>{
 
-    pub fn new(&self,x:FieldElement,y:FieldElement,z:FieldElement,a:FieldElement,b:FieldElement) -> Self{
+    pub fn new(x:F,y:F,z:F,a:F,b:F) -> Self{
         PointProjective{values: [x,y,z] , curve: [a,b]}
     }
-    pub fn get_x(&self) -> &FieldElement{
+    pub fn get_x(&self) -> &F{
         &self.values[0]
         
     }
-    pub fn get_y(&self) -> &FieldElement{
+    pub fn get_y(&self) -> &F{
         &self.values[1]
         
     }
-    pub fn get_z(&self) -> &FieldElement{
+    pub fn get_z(&self) -> &F{
         &self.values[2]
         
     }
-    pub fn get_a(&self) -> &FieldElement{
+    pub fn get_a(&self) -> &F{
         &self.curve[0]
         
     }
-    pub fn get_b(&self) -> &FieldElement{
+    pub fn get_b(&self) -> &F{
         &self.curve[1]
         
     }
@@ -101,29 +160,109 @@ impl PointProjective<FieldElement>{
     pub fn normalize_z(&self) -> Self{
         let z_inv =self.get_z().modinv();
 
-        let x = (self.get_x().clone() * z_inv.clone());
+        let x = self.get_x().clone() * z_inv.clone();
 
-        let y = (self.get_y().clone() * z_inv);
+        let y = self.get_y().clone() * z_inv;
         
 
-        let z = FieldElement::one(x.get_prime().clone());
+        let z = x.one_like();
         PointProjective{values: [x, y, z],curve:[self.get_a().clone(),self.get_b().clone()]}
     }
 
-}
+    pub fn identity(a:F,b:F) -> Self{
+        PointProjective{
+            values: [a.zero_like(),a.one_like(),a.zero_like()],
+            curve: [a,b]
+        }
+    }
 
+    pub fn is_identity(&self) -> bool{
+        self.get_z().is_zero()
+    }
 
+    pub fn conditional_swap(&mut self, other:&mut Self, choice:u8){
+        for (a,b) in self.values.iter_mut().zip(other.values.iter_mut()){
+            a.conditional_swap(b,choice);
+        }
+    }
 
-impl PointOperations for PointAffine<FieldElement>{
-    
-    fn add(&self , other: Self) -> Self {
-        let slope = ((self.get_y() - other.get_y()) / (self.get_x() - other.get_x())).clone();
-        let x_r = (&slope * &slope) - (self.get_x() - other.get_x()); 
+    pub fn negate(&self) -> Self{
+        PointProjective{values: [self.get_x().clone(),self.get_y().negate(),self.get_z().clone()],curve: self.curve.clone()}
+    }
 
-        let y_r = slope * (self.get_x() - &x_r) - self.get_y();
+    pub fn to_affine(&self) -> PointAffine<F>{
+        if self.is_identity(){
+            return PointAffine::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let normalized = self.normalize_z();
+        PointAffine{values: [normalized.values[0].clone(),normalized.values[1].clone()],curve: normalized.curve,infinity: false}
+    }
 
-        Self{values: [x_r,y_r], curve: [self.get_a().clone(),self.get_b().clone()]}
+    // With u = Y2 Z1 - Y1 Z2 and v = X2 Z1 - X1 Z2, v = 0 means P = Q or P = -Q:
+    //   A = u^2 Z1Z2 - v^3 - 2 v^2 X1Z2
+    //   X3 = v A, Y3 = u (v^2 X1Z2 - A) - v^3 Y1Z2, Z3 = v^3 Z1Z2
+    fn add_points(&self, other:&Self) -> Self{
+        if self.is_identity(){
+            return other.clone();
+        }
+        if other.is_identity(){
+            return self.clone();
+        }
+        let (x1,y1,z1) = (self.get_x(),self.get_y(),self.get_z());
+        let (x2,y2,z2) = (other.get_x(),other.get_y(),other.get_z());
+
+        let u = y2.clone() * z1 - y1.clone() * z2;
+        let v = x2.clone() * z1 - x1.clone() * z2;
+        if v.is_zero(){
+            if u.is_zero(){
+                return self.double();
+            }
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+
+        let z1z2 = z1.clone() * z2;
+        let vv = v.square();
+        let vvv = v.clone() * &vv;
+        let r = vv * x1 * z2;
+        let big_a = u.square() * &z1z2 - &vvv - &r - &r;
+        let x3 = v * &big_a;
+        let y3 = u * (r - big_a) - vvv.clone() * y1 * z2;
+        let z3 = vvv * z1z2;
 
+        PointProjective{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+    // w = a Z^2 + 3 X^2, s = 2 Y Z, B = 4 X Y^2 Z:
+    //   X3 = (w^2 - 2B) s, Y3 = w (3B - w^2) - 8 Y^4 Z^2, Z3 = s^3
+    fn double_point(&self) -> Self{
+        if self.is_identity() || self.get_y().is_zero(){
+            return Self::identity(self.get_a().clone(),self.get_b().clone());
+        }
+        let (x,y,z) = (self.get_x(),self.get_y(),self.get_z());
+
+        let xx = x.square();
+        let w = self.get_a().clone() * z.square() + &xx + &xx + &xx;
+        let s = (y.clone() + y) * z;
+        let ss = s.square();
+        let r = y.clone() * &s;
+        let rr = r.square();
+        let big_b = (x.clone() + &r).square() - xx - &rr;
+        let h = w.square() - &big_b - &big_b;
+        let x3 = h.clone() * &s;
+        let y3 = w * (big_b - h) - &rr - &rr;
+        let z3 = s * ss;
+
+        PointProjective{values: [x3,y3,z3],curve: self.curve.clone()}
+    }
+
+}
+
+
+
+impl<F: Field> PointOperations for PointAffine<F>{
+    
+    fn add(&self , other: Self) -> Self {
+        self.add_points(&other)
     }
 
 
@@ -131,115 +270,117 @@ impl PointOperations for PointAffine<FieldElement>{
     // fn multiply(&self, other:Self, n:num_bigint::BigInt) -> Self {}
 
     fn double(&self) -> Self {
-        let three = FieldElement::new_from_i32(3, self.get_x().get_prime().clone()).unwrap();
-        let two = FieldElement::new_from_i32(2, self.get_x().get_prime().clone()).unwrap();
+        if self.is_identity() || self.get_y().is_zero(){
+            return PointAffine::identity(self.curve[0].clone(),self.curve[1].clone());
+        }
+        let three = self.get_x().integer_like(3);
+        let two = self.get_x().integer_like(2);
         let x = self.get_x().clone();
         let y = self.get_y().clone();
 
-        let slope = ((three * &x * &x) + self.get_a()) / (&two * &y);
+        let slope = ((three * x.square()) + self.get_a()) / (two.clone() * &y);
         
-        let x_r = (&slope * &slope) - &two * &x;
+        let x_r = slope.square() - two * &x;
         
-        let y_r = &slope*(&x - &x_r) - &y;
+        let y_r = slope*(x - &x_r) - &y;
         
-        Self{ values: [x_r ,y_r] , curve: [self.curve[0].clone(), self.curve[1].clone() ]}
+        Self{ values: [x_r ,y_r] , curve: [self.curve[0].clone(), self.curve[1].clone() ], infinity: false}
 
     }
 
-    fn multiply(&self, mut n: isize) -> Self {
-        let mut Q = self.clone();
-
-        let prime = self.element_prime().clone();
-
-        let mut R = PointAffine{
-            values:[FieldElement::zero(prime.clone()),FieldElement::zero(prime)],
-            curve:[self.curve[0].clone(),self.curve[1].clone()]
-        };
-
-        while n>0 {
-            let coeff= n % 2;
-
-            println!("{}, {}",coeff,n);
+    fn multiply(&self, n: &BigInt) -> Self {
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
+        }
+        let mut q = self.clone();
 
-            if coeff==1 {
-                println!("done");
-                R = R + &Q;
+        let mut r = PointAffine::identity(self.curve[0].clone(),self.curve[1].clone());
 
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r += &q;
             }
-            Q = Q.double();
-            n = n/2;
-        }       
-        R
+            q = q.double();
+        }
+        r
 
     }
 
 }
 
 
-impl Add<PointAffine<FieldElement>> for PointAffine<FieldElement>{
-    type Output = Self;
+// General projective formulas with the exceptional cases handled by branching,
+// valid on every curve. The complete formulas of `add_complete` are an opt-in
+// alternative for curves of odd order.
+impl<F: Field> PointOperations for PointProjective<F>{
 
+    fn add(&self, other: Self) -> Self{
+        self.add_points(&other)
+    }
 
-    fn add(self,other:PointAffine<FieldElement>) -> Self{
-        if(self.get_x().get_number() == &BigInt::from(0) && self.get_y().get_number() == &BigInt::from(0) ){
-            other
-        }
-        else if other.get_x() == &FieldElement::zero(self.element_prime()) && other.get_y() == &FieldElement::zero(self.element_prime()) {
-            self
-        }
-        else if (self.get_x() == other.get_x() && self.get_y() == other.get_y()){
-            self.double()
+    fn double(&self) -> Self{
+        self.double_point()
+    }
+
+    fn multiply(&self, n: &BigInt) -> Self{
+        if n.is_negative(){
+            return self.negate().multiply(&-n);
         }
-        else {
-            let slope = (self.get_y() - other.get_y()) / (self.get_x() - other.get_x());
-        
-            let x_r = &slope * &slope - self.get_x() - other.get_x(); 
-    
-            let y_r = slope * (self.get_x() - &x_r) - self.get_y();
-    
-            Self{values: [x_r,y_r],curve: [self.curve[0].clone(),self.curve[1].clone()]}
+        let mut q = self.clone();
+        let mut r = Self::identity(self.get_a().clone(),self.get_b().clone());
 
+        for i in 0..n.bits() {
+            if n.magnitude().bit(i) {
+                r = r.add_points(&q);
+            }
+            q = q.double_point();
         }
+        r
+    }
+
+}
 
 
+impl<F: Field> Add<PointAffine<F>> for PointAffine<F>{
+    type Output = Self;
 
+
+    fn add(self,other:PointAffine<F>) -> Self{
+        self.add_points(&other)
     }
 
 }
 
 
-impl Add<&PointAffine<FieldElement>> for PointAffine<FieldElement>{
+impl<F: Field> Add<&PointAffine<F>> for PointAffine<F>{
     type Output = Self;
 
 
-    fn add(self, other:&PointAffine<FieldElement>) -> Self{
-        let vals = self.get_values();
-        if vals[0].get_number() == &BigInt::from(0) && vals[1].get_number() == &BigInt::from(0) {
-            other.clone()
-        }
-        else if other.get_x() == &FieldElement::zero(self.element_prime()) && other.get_y() == &FieldElement::zero(self.element_prime()) {
-            self
-        }
-        else if (self.get_x() == other.get_x() && self.get_y() == other.get_y()){
-            self.double()
-        }
-        else {
-            let slope = (self.get_y() - other.get_y()) / (self.get_x() - other.get_x());
-        
-            let x_r = &slope * &slope - self.get_x() - other.get_x(); 
-    
-            let y_r = slope * (self.get_x() - &x_r) - self.get_y();
-    
-            Self{values: [x_r,y_r],curve: [self.curve[0].clone(),self.curve[1].clone()]}
+    fn add(self, other:&PointAffine<F>) -> Self{
+        self.add_points(other)
+    }
 
-        }
+}
 
 
-    }
 
+impl<F: Field> Mul<&BigInt> for &PointAffine<F>{
+    type Output = PointAffine<F>;
+
+    fn mul(self, n: &BigInt) -> Self::Output{
+        self.multiply(n)
+    }
 }
 
 
+impl<F: Field> Mul<&PointAffine<F>> for &BigInt{
+    type Output = PointAffine<F>;
+
+    fn mul(self, point: &PointAffine<F>) -> Self::Output{
+        point.multiply(self)
+    }
+}
+
 
 
 #[test]
@@ -286,9 +427,55 @@ fn multiply_point(){
     let p1 = PointAffine::new(x,y,a.clone(),b.clone());
     let p2 = PointAffine::new(x2,y2,a,b);
 
-    let p1_5 = p1.multiply(4);
+    let p1_5 = p1.multiply(&BigInt::from(4));
 
     assert_eq!(p1_5,p2);
     
 }
 
+
+
+
+#[test]
+fn multiply_bigint_scalars(){
+
+    let x = FieldElement::new(BigInt::from(3_u32),BigInt::from(7_u32)).unwrap();
+    let y = FieldElement::new(BigInt::from(3_u32),BigInt::from(7_u32)).unwrap();
+    let a = FieldElement::new(BigInt::from(0_u32),BigInt::from(7_u32)).unwrap();
+    let b = FieldElement::new(BigInt::from(3_u32),BigInt::from(7_u32)).unwrap();
+
+    let p1 = PointAffine::new(x,y,a,b);
+
+    // the curve has 13 points, so k and k + 13 * 2^200 give the same multiple
+    let k = BigInt::from(5);
+    let big_k = &k + BigInt::from(13) * (BigInt::from(1) << 200);
+    assert_eq!(p1.multiply(&big_k),p1.multiply(&k));
+
+    assert_eq!(&k * &p1,&p1 * &k);
+    assert_eq!(p1.multiply(&-&k),p1.multiply(&k).negate());
+    assert_eq!(p1.multiply(&-&k),p1.multiply(&BigInt::from(8)));
+    assert!(p1.multiply(&BigInt::from(13)).is_identity());
+    assert!(p1.multiply(&BigInt::from(0)).is_identity());
+
+}
+
+
+#[test]
+fn identity_is_not_the_origin(){
+    // y^2 = x^3 + x over GF(11), where (0, 0) is a point of order two
+    let a = FieldElement::new(BigInt::from(1),BigInt::from(11)).unwrap();
+    let b = FieldElement::zero(BigInt::from(11));
+    let origin = PointAffine::new(b.clone(),b.clone(),a.clone(),b.clone());
+    let identity = PointAffine::identity(a,b);
+
+    assert!(origin.is_on_curve());
+    assert!(!origin.is_identity());
+    assert_ne!(origin,identity);
+    assert_eq!(origin.negate(),origin);
+    assert!(origin.double().is_identity());
+    assert_eq!(origin.clone() + &identity,origin);
+    assert_eq!(identity.clone() + &origin,origin);
+    assert!((origin.clone() + &origin).is_identity());
+    assert_eq!(origin.multiply(&BigInt::from(3)),origin);
+    assert_eq!(origin.to_projective().to_affine(),origin);
+}
@@ -0,0 +1,150 @@
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::jacobian::PointJacobian;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+pub const DEFAULT_WINDOW: usize = 4;
+
+
+// Width-w non-adjacent form of k, least significant digit first. Every non-zero
+// digit is odd with |d| < 2^(w-1), and any w consecutive digits contain at most
+// one non-zero digit. The window is capped at 16, the table of odd multiples
+// already holds 2^14 points there.
+pub fn wnaf(k:&BigInt, w:usize) -> Result<Vec<i64>,FieldErrors>{
+    check_window(w)?;
+
+    let modulus = BigInt::from(1_i64 << w);
+    let half = 1_i64 << (w - 1);
+    let mut k = k.clone();
+    let mut digits = Vec::new();
+
+    while !k.is_zero(){
+        let mut digit = 0_i64;
+        if k.magnitude().bit(0){
+            let mut r = &k % &modulus;
+            if r.is_negative(){
+                r += &modulus;
+            }
+            digit = i64::try_from(r).unwrap();
+            if digit >= half{
+                digit -= 1_i64 << w;
+            }
+            k -= digit;
+        }
+        digits.push(digit);
+        k >>= 1;
+    }
+    Ok(digits)
+}
+
+
+// Odd multiples P, 3P, 5P, ..., (2^(w-1) - 1)P
+pub fn odd_multiples(point:&PointJacobian<FieldElement>, w:usize) -> Result<Vec<PointJacobian<FieldElement>>,FieldErrors>{
+    check_window(w)?;
+    let count = 1_usize << (w - 2);
+    let double = point.double();
+    let mut table = Vec::with_capacity(count);
+    table.push(point.clone());
+    for i in 1..count{
+        let next = table[i - 1].add(double.clone());
+        table.push(next);
+    }
+    Ok(table)
+}
+
+
+fn check_window(w:usize) -> Result<(),FieldErrors>{
+    if !(2..=16).contains(&w){
+        return Err(FieldErrors::InvalidParams("Window size must be between 2 and 16".to_string()));
+    }
+    Ok(())
+}
+
+
+pub fn mul_wnaf_jacobian(point:&PointJacobian<FieldElement>, k:&BigInt, w:usize) -> Result<PointJacobian<FieldElement>,FieldErrors>{
+    let digits = wnaf(k,w)?;
+    let table = odd_multiples(point,w)?;
+    let mut r = PointJacobian::identity(point.get_a().clone(),point.get_b().clone());
+
+    for digit in digits.iter().rev(){
+        r = r.double();
+        if *digit > 0{
+            r = r.add(table[(*digit as usize) / 2].clone());
+        }
+        else if *digit < 0{
+            r = r.add(table[(-*digit as usize) / 2].negate());
+        }
+    }
+    Ok(r)
+}
+
+
+// Variable time, only for public scalars such as the ones in signature
+// verification. Use `multiply_ladder` for secrets.
+impl PointAffine<FieldElement>{
+
+    pub fn mul_wnaf(&self, k:&BigInt, w:usize) -> Result<Self,FieldErrors>{
+        Ok(mul_wnaf_jacobian(&self.to_jacobian(),k,w)?.to_affine())
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::field;
+
+
+#[test]
+fn wnaf_recoding(){
+    for w in 2..7{
+        for k in -300..300{
+            let k = BigInt::from(k);
+            let digits = wnaf(&k,w).unwrap();
+
+            let mut value = BigInt::zero();
+            for (i,digit) in digits.iter().enumerate(){
+                value += BigInt::from(*digit) << i;
+                if *digit != 0{
+                    assert!(digit % 2 != 0 && digit.abs() < (1 << (w - 1)));
+                    assert!(digits[i+1..].iter().take(w - 1).all(|d| *d == 0));
+                }
+            }
+            assert_eq!(value,k);
+        }
+    }
+}
+
+
+#[test]
+fn wnaf_matches_double_and_add(){
+    // y^2 = x^3 + 3x + 2 over GF(97) has prime order 103
+    let p1 = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+
+    for w in 2..7{
+        for k in -110..110{
+            let k = BigInt::from(k);
+            assert_eq!(p1.mul_wnaf(&k,w).unwrap(),p1.multiply(&k));
+        }
+    }
+
+    let k = BigInt::from(1) << 300;
+    assert_eq!(p1.mul_wnaf(&k,DEFAULT_WINDOW).unwrap(),p1.multiply(&k));
+}
+
+
+#[test]
+fn wnaf_rejects_bad_windows(){
+    let p1 = PointAffine::new(field(3,7),field(3,7),field(0,7),field(3,7));
+    for w in [0,1,17]{
+        assert!(p1.mul_wnaf(&BigInt::from(5),w).is_err());
+        assert!(wnaf(&BigInt::from(5),w).is_err());
+        assert!(odd_multiples(&p1.to_jacobian(),w).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+use std::ops::{Add,Sub,Mul,Neg};
+use std::fmt;
+
+
+// An integer modulo the order of the group generated by a base point. Scalars
+// are always kept reduced to [0, order).
+#[derive(Debug,Clone,PartialEq)]
+pub struct Scalar{
+    n: BigInt,
+    order: BigInt,
+}
+
+
+impl Scalar{
+
+    pub fn new(number:BigInt,order:BigInt) -> Result<Scalar,FieldErrors>{
+        if !order.is_positive(){
+            return Err(FieldErrors::InvalidParams("Group order must be positive".to_string()));
+        }
+        Ok(Scalar { n: reduce(&number,&order), order })
+    }
+
+    pub fn get_number(&self) -> &BigInt{
+        &self.n
+    }
+
+    pub fn get_order(&self) -> &BigInt{
+        &self.order
+    }
+
+    pub fn is_zero(&self) -> bool{
+        self.n.is_zero()
+    }
+
+    // bit length of the group order, every scalar fits in this many bits
+    pub fn bit_length(&self) -> u64{
+        self.order.bits()
+    }
+
+}
+
+
+fn reduce(n:&BigInt, order:&BigInt) -> BigInt{
+    let r = n % order;
+    if r.is_negative(){
+        r + order
+    }
+    else{
+        r
+    }
+}
+
+
+// The operators panic on scalars of different orders, the checked versions
+// return Mismatch instead
+impl Scalar{
+
+    pub fn checked_add(&self, other:&Scalar) -> Result<Scalar,FieldErrors>{
+        self.combine(other,&self.n + &other.n)
+    }
+
+    pub fn checked_sub(&self, other:&Scalar) -> Result<Scalar,FieldErrors>{
+        self.combine(other,&self.n - &other.n)
+    }
+
+    pub fn checked_mul(&self, other:&Scalar) -> Result<Scalar,FieldErrors>{
+        self.combine(other,&self.n * &other.n)
+    }
+
+    fn combine(&self, other:&Scalar, n:BigInt) -> Result<Scalar,FieldErrors>{
+        if self.order != other.order{
+            return Err(FieldErrors::Mismatch(format!("Scalars modulo {} and {} cannot be combined",self.order,other.order)));
+        }
+        Ok(Scalar { n: reduce(&n,&self.order), order: self.order.clone() })
+    }
+
+}
+
+
+impl Add<&Scalar> for &Scalar{
+    type Output = Scalar;
+
+    fn add(self, other: &Scalar) -> Scalar{
+        self.checked_add(other).unwrap()
+    }
+}
+
+
+impl Sub<&Scalar> for &Scalar{
+    type Output = Scalar;
+
+    fn sub(self, other: &Scalar) -> Scalar{
+        self.checked_sub(other).unwrap()
+    }
+}
+
+
+impl Mul<&Scalar> for &Scalar{
+    type Output = Scalar;
+
+    fn mul(self, other: &Scalar) -> Scalar{
+        self.checked_mul(other).unwrap()
+    }
+}
+
+
+impl Neg for &Scalar{
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar{
+        Scalar { n: reduce(&-&self.n,&self.order), order: self.order.clone() }
+    }
+}
+
+
+impl Mul<&Scalar> for &PointAffine<FieldElement>{
+    type Output = PointAffine<FieldElement>;
+
+    fn mul(self, k: &Scalar) -> Self::Output{
+        self.multiply(k.get_number())
+    }
+}
+
+
+impl Mul<&PointAffine<FieldElement>> for &Scalar{
+    type Output = PointAffine<FieldElement>;
+
+    fn mul(self, point: &PointAffine<FieldElement>) -> Self::Output{
+        point.multiply(self.get_number())
+    }
+}
+
+
+impl fmt::Display for Scalar{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        write!(f,"Scalar : {} , Group Order :{}",self.n,self.order)
+
+    }
+}
+
+
+
+
+#[test]
+fn scalar_reduction(){
+    let order = BigInt::from(13);
+
+    let a = Scalar::new(BigInt::from(-5),order.clone()).unwrap();
+    assert_eq!(a.get_number(),&BigInt::from(8));
+
+    let b = Scalar::new(BigInt::from(30),order.clone()).unwrap();
+    assert_eq!(b.get_number(),&BigInt::from(4));
+
+    assert_eq!((&a + &b).get_number(),&BigInt::from(12));
+    assert_eq!((&a - &b).get_number(),&BigInt::from(4));
+    assert_eq!((&a * &b).get_number(),&BigInt::from(6));
+    assert!((&a + &-&a).is_zero());
+
+    assert!(Scalar::new(BigInt::from(1),BigInt::from(0)).is_err());
+}
+
+
+#[test]
+fn scalar_order_mismatch(){
+    let a = Scalar::new(BigInt::from(5),BigInt::from(13)).unwrap();
+    let b = Scalar::new(BigInt::from(5),BigInt::from(17)).unwrap();
+    assert!(matches!(a.checked_add(&b),Err(FieldErrors::Mismatch(_))));
+    assert!(matches!(a.checked_sub(&b),Err(FieldErrors::Mismatch(_))));
+    assert!(matches!(b.checked_mul(&a),Err(FieldErrors::Mismatch(_))));
+    assert_eq!(a.checked_add(&a).unwrap(),&a + &a);
+
+    let result = std::panic::catch_unwind(|| &a * &b);
+    assert!(result.is_err());
+}
+
+
+#[test]
+fn scalar_point_multiplication(){
+    let p = BigInt::from(7);
+    let x = FieldElement::new(BigInt::from(3),p.clone()).unwrap();
+    let y = FieldElement::new(BigInt::from(3),p.clone()).unwrap();
+    let a = FieldElement::new(BigInt::from(0),p.clone()).unwrap();
+    let b = FieldElement::new(BigInt::from(3),p).unwrap();
+    let p1 = PointAffine::new(x,y,a,b);
+
+    let k = Scalar::new(BigInt::from(-4),BigInt::from(13)).unwrap();
+
+    assert_eq!(&k * &p1,p1.multiply(&BigInt::from(-4)));
+    assert_eq!(&p1 * &k,&k * &p1);
+}
@@ -0,0 +1,251 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::point::msm::msm_straus;
+use crate::elliptic_curve::number_theory::gcd;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Membership in the subgroup of prime order n generated by the declared
+// generator. #E(Fp) = h n, and a point with a component outside <G> lets an
+// attacker learn the secret scalar modulo the small orders it carries.
+impl EllipticCurve{
+
+    pub fn subgroup_order(&self) -> Result<&BigInt,FieldErrors>{
+        self.get_order().ok_or(FieldErrors::InvalidParams("Curve has no group order set".to_string()))
+    }
+
+    // h = #E(Fp) / n, the declared cofactor or else one computed by point counting
+    pub fn cofactor(&self) -> Result<BigInt,FieldErrors>{
+        if let Some(cofactor) = self.get_cofactor(){
+            return Ok(cofactor.clone());
+        }
+        let n = self.subgroup_order()?;
+        let order = self.order()?;
+        if !(&order % n).is_zero(){
+            return Err(FieldErrors::Mismatch("Subgroup order does not divide the group order".to_string()));
+        }
+        Ok(order / n)
+    }
+
+    // [h]P, maps any curve point into the subgroup of order n
+    pub fn clear_cofactor(&self, point:&PointAffine<FieldElement>) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let cofactor = self.cofactor()?;
+        if cofactor.is_one(){
+            return Ok(point.clone());
+        }
+        Ok(point.multiply(&cofactor))
+    }
+
+    // [h]P = O, the points that cofactor clearing sends to the identity
+    pub fn is_small_order(&self, point:&PointAffine<FieldElement>) -> Result<bool,FieldErrors>{
+        Ok(self.clear_cofactor(point)?.is_identity())
+    }
+
+    // [n]P = O, true exactly for the points of the prime order subgroup
+    pub fn is_torsion_free(&self, point:&PointAffine<FieldElement>) -> Result<bool,FieldErrors>{
+        let order = self.subgroup_order()?;
+        Ok(point.multiply(order).is_identity())
+    }
+
+    // Same answer as `is_torsion_free` for points of the curve, with shortcuts:
+    // with h = 1 every point lies in the subgroup, and with a GLV endomorphism
+    // the check runs on two half length scalars (see `endomorphism_check`).
+    pub fn is_in_prime_subgroup(&self, point:&PointAffine<FieldElement>) -> Result<bool,FieldErrors>{
+        if !self.contains(point){
+            return Ok(false);
+        }
+        let order = self.subgroup_order()?;
+        let cofactor = self.cofactor()?;
+        if cofactor.is_one(){
+            return Ok(true);
+        }
+        if self.get_endomorphism().is_some() && gcd(order,&cofactor).is_one(){
+            return self.endomorphism_check(point);
+        }
+        self.is_torsion_free(point)
+    }
+
+    // SP 800-56A rev. 3, 5.6.2.3.4: the key is not the identity, its
+    // coordinates are reduced modulo p and it satisfies the curve equation.
+    // Enough for ECDH with h = 1 or with cofactor multiplication.
+    pub fn partial_public_key_validation(&self, x:&BigInt, y:&BigInt) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let p = self.get_prime();
+        if x < &BigInt::zero() || x >= p || y < &BigInt::zero() || y >= p{
+            return Err(FieldErrors::InvalidParams("Public key coordinates are not in [0, p - 1]".to_string()));
+        }
+        self.point(x.clone(),y.clone())
+    }
+
+    // SP 800-56A rev. 3, 5.6.2.3.3: partial validation followed by [n]Q = O
+    pub fn full_public_key_validation(&self, x:&BigInt, y:&BigInt) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let point = self.partial_public_key_validation(x,y)?;
+        if !self.is_in_prime_subgroup(&point)?{
+            return Err(FieldErrors::PointNotOnCurve("Public key is not in the subgroup of order n".to_string()));
+        }
+        Ok(point)
+    }
+
+    // Full validation of a SEC1 encoded public key
+    pub fn validate_encoded_public_key(&self, bytes:&[u8]) -> Result<PointAffine<FieldElement>,FieldErrors>{
+        let point = PointAffine::from_encoded_point(bytes,self)?;
+        if point.is_identity(){
+            return Err(FieldErrors::PointNotOnCurve("Public key is the identity".to_string()));
+        }
+        self.full_public_key_validation(point.get_x().get_number(),point.get_y().get_number())
+    }
+
+    // The GLV lattice vectors (a, b) satisfy a + b lambda = 0 mod n, so points of
+    // <G> have [a]P + [b] phi(P) = O. If that holds for both basis vectors then
+    // [a1 b2 - a2 b1] P = [n] P = O up to sign, and since gcd(n, h) = 1 makes
+    // the n-part of E(Fp) cyclic, P is in <G>.
+    fn endomorphism_check(&self, point:&PointAffine<FieldElement>) -> Result<bool,FieldErrors>{
+        let endomorphism = self.get_endomorphism()
+            .ok_or(FieldErrors::InvalidParams("Curve has no endomorphism set".to_string()))?;
+        let image = endomorphism.apply(point);
+        for (a,b) in endomorphism.get_basis().iter(){
+            if !msm_straus(&[a.clone(),b.clone()],&[point.clone(),image.clone()])?.to_affine().is_identity(){
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::glv::GlvEndomorphism;
+
+
+// y^2 = x^3 + 1 over GF(1009) has 948 = 12 * 79 points and a = 0, p = 1 mod 3
+#[cfg(test)]
+fn curve_with_cofactor() -> EllipticCurve{
+    let p = BigInt::from(1009);
+    let curve = EllipticCurve::new(FieldElement::zero(p.clone()),FieldElement::one(p)).unwrap();
+    let generator = curve.points().skip(1).map(|point| point.multiply(&BigInt::from(12))).find(|point| !point.is_identity()).unwrap();
+    let order = BigInt::from(79);
+    let endomorphism = GlvEndomorphism::find(&generator,&order).unwrap();
+    curve.with_generator(generator,order,BigInt::from(12)).unwrap().with_endomorphism(endomorphism).unwrap()
+}
+
+
+#[test]
+fn subgroup_membership(){
+    let curve = curve_with_cofactor();
+    let order = BigInt::from(79);
+    let naive = EllipticCurve::new(curve.get_a().clone(),curve.get_b().clone()).unwrap()
+        .with_generator(curve.get_generator().unwrap().clone(),order.clone(),BigInt::from(12)).unwrap();
+    assert!(naive.get_endomorphism().is_none());
+
+    let mut members = 0;
+    for point in curve.points(){
+        let expected = point.multiply(&order).is_identity();
+        assert_eq!(curve.is_torsion_free(&point).unwrap(),expected);
+        assert_eq!(curve.is_in_prime_subgroup(&point).unwrap(),expected);
+        assert_eq!(naive.is_in_prime_subgroup(&point).unwrap(),expected);
+        let cleared = curve.clear_cofactor(&point).unwrap();
+        assert!(curve.is_in_prime_subgroup(&cleared).unwrap());
+        assert_eq!(curve.is_small_order(&point).unwrap(),point.multiply(&BigInt::from(12)).is_identity());
+        members += expected as usize;
+    }
+    assert_eq!(members,79);
+
+    let other = EllipticCurve::new(FieldElement::zero(BigInt::from(1009)),FieldElement::new(BigInt::from(3),BigInt::from(1009)).unwrap()).unwrap();
+    assert!(other.is_in_prime_subgroup(&other.points().nth(1).unwrap()).is_err());
+    assert!(!curve.is_in_prime_subgroup(&other.points().nth(1).unwrap()).unwrap());
+}
+
+
+#[test]
+fn cofactor_metadata(){
+    use crate::elliptic_curve::curves::secp256r1;
+
+    let curve = curve_with_cofactor();
+    assert_eq!(curve.cofactor().unwrap(),BigInt::from(12));
+    assert_eq!(curve.subgroup_order().unwrap(),&BigInt::from(79));
+    assert!(EllipticCurve::new(FieldElement::zero(BigInt::from(1009)),FieldElement::one(BigInt::from(1009))).unwrap().cofactor().is_err());
+
+    let curve = secp256r1();
+    assert_eq!(curve.cofactor().unwrap(),BigInt::one());
+    let g = curve.get_generator().unwrap();
+    assert_eq!(&curve.clear_cofactor(g).unwrap(),g);
+    assert!(curve.is_in_prime_subgroup(g).unwrap());
+}
+
+
+#[test]
+fn public_key_validation(){
+    use crate::elliptic_curve::curves::{secp256k1, secp256r1};
+
+    for curve in [secp256k1(),secp256r1()]{
+        let p = curve.get_prime().clone();
+        let q = curve.get_generator().unwrap().multiply(&BigInt::from(0xdecaf));
+        let (x,y) = (q.get_x().get_number().clone(),q.get_y().get_number().clone());
+        assert_eq!(curve.full_public_key_validation(&x,&y).unwrap(),q);
+        assert_eq!(curve.validate_encoded_public_key(&q.to_encoded_point(true)).unwrap(),q);
+
+        assert!(curve.partial_public_key_validation(&(&x + &p),&y).is_err());
+        assert!(curve.partial_public_key_validation(&x,&(&p - &y + &p)).is_err());
+        assert!(curve.partial_public_key_validation(&x,&(&y + 1)).is_err());
+        assert!(curve.partial_public_key_validation(&BigInt::zero(),&BigInt::zero()).is_err());
+        assert!(curve.validate_encoded_public_key(&[0x00]).is_err());
+    }
+
+    // a point of order 3 passes partial validation and fails full validation
+    let curve = curve_with_cofactor();
+    let small = curve.points().skip(1).find(|point| point.multiply(&BigInt::from(3)).is_identity()).unwrap();
+    let (x,y) = (small.get_x().get_number(),small.get_y().get_number());
+    assert!(curve.partial_public_key_validation(x,y).is_ok());
+    assert!(curve.full_public_key_validation(x,y).is_err());
+    assert!(curve.validate_encoded_public_key(&small.to_encoded_point(false)).is_err());
+    let g = curve.get_generator().unwrap();
+    assert!(curve.full_public_key_validation(g.get_x().get_number(),g.get_y().get_number()).is_ok());
+}
+
+
+#[test]
+fn endomorphism_multiplication_outside_subgroup(){
+    // phi acts as lambda on <G> only, the other points must not be decomposed
+    let curve = curve_with_cofactor();
+    let endomorphism = curve.get_endomorphism().unwrap();
+    let g = curve.get_generator().unwrap();
+    let cofactor = BigInt::from(12);
+    for point in curve.points().step_by(7){
+        for k in [2,5,78,1000,-31]{
+            let k = BigInt::from(k);
+            assert_eq!(curve.multiply(&point,&k),point.multiply(&k));
+            assert_eq!(endomorphism.multiply(&point,&k,&cofactor),point.multiply(&k));
+        }
+    }
+    let k = BigInt::from(1000);
+    assert_eq!(curve.multiply(g,&k),g.multiply(&k));
+    assert_eq!(endomorphism.multiply_in_subgroup(g,&k),g.multiply(&k));
+
+    // a generator must have the declared order
+    let naive = EllipticCurve::new(curve.get_a().clone(),curve.get_b().clone()).unwrap();
+    assert!(naive.clone().with_generator(g.clone(),BigInt::from(78),BigInt::from(12)).is_err());
+    let outside = curve.points().find(|point| !point.multiply(&BigInt::from(79)).is_identity()).unwrap();
+    assert!(naive.clone().with_generator(outside,BigInt::from(79),BigInt::from(12)).is_err());
+    assert!(naive.clone().with_generator(g.clone(),BigInt::zero(),BigInt::from(12)).is_err());
+    assert!(naive.clone().with_generator(g.clone(),BigInt::one(),BigInt::from(12)).is_err());
+    assert!(naive.clone().with_generator(g.clone(),BigInt::from(79),BigInt::zero()).is_err());
+
+    // the endomorphism is checked against the generator in either order
+    let endomorphism = curve.get_endomorphism().unwrap().clone();
+    let with_endomorphism = naive.clone().with_endomorphism(endomorphism.clone()).unwrap();
+    assert!(with_endomorphism.with_generator(g.clone(),BigInt::from(79),BigInt::from(12)).is_ok());
+    let wrong_lambda = GlvEndomorphism::new(endomorphism.get_beta().clone(),endomorphism.get_lambda().modpow(&BigInt::from(2),&BigInt::from(79)),BigInt::from(79)).unwrap();
+    let with_wrong = naive.clone().with_endomorphism(wrong_lambda).unwrap();
+    assert!(with_wrong.with_generator(g.clone(),BigInt::from(79),BigInt::from(12)).is_err());
+    let generated = naive.with_generator(g.clone(),BigInt::from(79),BigInt::from(12)).unwrap();
+    let wrong_order = GlvEndomorphism::new(endomorphism.get_beta().clone(),BigInt::from(3),BigInt::from(13)).unwrap();
+    assert!(generated.with_endomorphism(wrong_order).is_err());
+}
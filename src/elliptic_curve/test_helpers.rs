@@ -0,0 +1,35 @@
+use num_bigint::BigInt;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+// Fixtures shared by the unit tests.
+
+
+// n mod p as an element of GF(p), negative n allowed
+pub fn field(n:i32,p:i32) -> FieldElement{
+    FieldElement::new_from_i32((n % p + p) % p, BigInt::from(p)).unwrap()
+}
+
+// y^2 = x^3 + ax + b over GF(p)
+pub fn small_curve(a:i32, b:i32, p:i32) -> EllipticCurve{
+    EllipticCurve::new(field(a,p),field(b,p)).unwrap()
+}
+
+// small_curve for primes beyond i32
+pub fn curve_from_u64(a:u64, b:u64, p:u64) -> EllipticCurve{
+    let p = BigInt::from(p);
+    EllipticCurve::new(FieldElement::new(BigInt::from(a),p.clone()).unwrap(),FieldElement::new(BigInt::from(b),p).unwrap()).unwrap()
+}
+
+// n scalars of mixed sign and size with multiples of a generator of the prime
+// order 103 group of y^2 = x^3 + 3x + 2 over GF(97)
+pub fn msm_inputs(n:usize) -> (Vec<BigInt>,Vec<PointAffine<FieldElement>>){
+    let g = PointAffine::new(field(0,97),field(14,97),field(3,97),field(2,97));
+    let scalars = (0..n).map(|i| (BigInt::from(i * 7919 + 13) << (i % 5 * 20)) * if i % 3 == 0 {-1} else {1}).collect();
+    let points = (0..n).map(|i| g.multiply(&BigInt::from(i + 1))).collect();
+    (scalars,points)
+}
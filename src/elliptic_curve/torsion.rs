@@ -0,0 +1,264 @@
+use num_bigint::BigInt;
+use num_traits::One;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{Field, FieldElementTraits};
+use crate::elliptic_curve::extension_field::{ExtensionField, ExtensionFieldElement};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::counting::{order_of_point, GroupStructure};
+use crate::elliptic_curve::number_theory::{gcd, lcm, SplitMix64};
+use crate::polynomials::polynomial::Polynomial;
+use crate::polynomials::division::{DivisionPolynomials, curve_polynomial};
+
+
+// seed of the Cantor-Zassenhaus samples, any value works
+const ROOT_SEED: u64 = 0x2545f4914f6cdd1d;
+
+
+// n-torsion of a curve over Fp, over Fp itself and over extensions GF(p^k).
+// E[n] minus O has its x coordinates among the roots of the torsion polynomial
+// of `DivisionPolynomials`, so the points over a field are the roots that lie
+// in it together with the square roots of x^3 + ax + b that lie in it.
+impl EllipticCurve{
+
+    // E(Fp)[n], sorted with the identity first
+    pub fn torsion_points(&self, n:usize) -> Result<Vec<PointAffine<FieldElement>>,FieldErrors>{
+        let p = self.get_prime().clone();
+        let lift = |c:&BigInt| FieldElement::new(c.clone(),p.clone()).unwrap();
+        let mut rng = SplitMix64::new(ROOT_SEED);
+        let sampler = || FieldElement::new(rng.below(&p),p.clone()).unwrap();
+        self.torsion_points_with(n,lift,sampler)
+    }
+
+    // E(GF(p^k))[n] for an extension of the field of the curve
+    pub fn torsion_points_over(&self, field:&ExtensionField, n:usize) -> Result<Vec<PointAffine<ExtensionFieldElement>>,FieldErrors>{
+        if field.get_prime() != self.get_prime(){
+            return Err(FieldErrors::Mismatch("Extension field has a different characteristic".to_string()));
+        }
+        let lift = |c:&BigInt| field.from_number(c.clone()).unwrap();
+        let mut rng = SplitMix64::new(ROOT_SEED);
+        let sampler = || field.from_number(rng.below(&field.order())).unwrap();
+        self.torsion_points_with(n,lift,sampler)
+    }
+
+    // E(Fp)[n] is Z/n1 x Z/n2 with n2 | n1 | n
+    pub fn torsion_structure(&self, n:usize) -> Result<GroupStructure,FieldErrors>{
+        let points = self.torsion_points(n)?;
+        let n_big = BigInt::from(n);
+        let exponent = points.iter().fold(BigInt::one(),|e,point| lcm(&e,&order_of_point(point,&n_big)));
+        let n2 = BigInt::from(points.len()) / &exponent;
+        Ok(GroupStructure::new(exponent,n2))
+    }
+
+    // Roots of x^3 + ax + b in Fp, the x coordinates of the points of order two.
+    pub fn two_torsion_x(&self) -> Vec<FieldElement>{
+        let p = self.get_prime().clone();
+        let mut rng = SplitMix64::new(ROOT_SEED);
+        let sampler = || FieldElement::new(rng.below(&p),p.clone()).unwrap();
+        let mut xs = roots(&curve_polynomial(self),sampler);
+        xs.sort_by(|x1,x2| x1.get_number().cmp(x2.get_number()));
+        xs
+    }
+
+    // #E(Fp) is odd exactly when there is no point of order two, the condition
+    // for the complete formulas of `PointProjective::add_complete`
+    pub fn has_odd_order(&self) -> bool{
+        self.two_torsion_x().is_empty()
+    }
+
+    // E[n] is contained in E(Fp), that is E(Fp)[n] = Z/n x Z/n
+    pub fn has_full_torsion(&self, n:usize) -> Result<bool,FieldErrors>{
+        Ok(self.torsion_points(n)?.len() == n * n)
+    }
+
+    // Smallest k <= max_degree with n | p^k - 1, the degree of the extension
+    // the Weil and Tate pairings on a subgroup of order n map into.
+    pub fn embedding_degree(&self, n:&BigInt, max_degree:u64) -> Option<u64>{
+        let p = self.get_prime();
+        if n <= &BigInt::one() || !gcd(n,p).is_one(){
+            return None;
+        }
+        let mut power = p % n;
+        for k in 1..=max_degree{
+            if power.is_one(){
+                return Some(k);
+            }
+            power = power * p % n;
+        }
+        None
+    }
+
+    // Smallest k <= max_degree with E[n] contained in E(GF(p^k)). The Weil
+    // pairing forces n | p^k - 1 first, so only those k are tried.
+    pub fn full_torsion_degree(&self, n:usize, max_degree:u64) -> Result<Option<u64>,FieldErrors>{
+        let n_big = BigInt::from(n);
+        let Some(first) = self.embedding_degree(&n_big,max_degree) else {
+            return Ok(None);
+        };
+        for k in (first..=max_degree).step_by(first as usize){
+            let count = if k == 1{
+                self.torsion_points(n)?.len()
+            }
+            else{
+                let field = ExtensionField::with_degree(self.get_prime().clone(),k as usize)?;
+                self.torsion_points_over(&field,n)?.len()
+            };
+            if count == n * n{
+                return Ok(Some(k));
+            }
+        }
+        Ok(None)
+    }
+
+    fn torsion_points_with<F: Field>(&self, n:usize, lift:impl Fn(&BigInt) -> F, sampler:impl FnMut() -> F) -> Result<Vec<PointAffine<F>>,FieldErrors>{
+        if n == 0{
+            return Err(FieldErrors::InvalidParams("Every point has order dividing 0".to_string()));
+        }
+        let a = lift(self.get_a().get_number());
+        let b = lift(self.get_b().get_number());
+        let torsion = DivisionPolynomials::new(self,n).torsion_polynomial(n)?;
+        let torsion = Polynomial::new(torsion.get_coefficients().iter().map(|c| lift(c.get_number())).collect());
+
+        let mut points = vec![];
+        for x in roots(&torsion,sampler){
+            let rhs = x.square() * &x + a.clone() * &x + &b;
+            if let Some(y) = rhs.sqrt(){
+                if !y.is_zero(){
+                    points.push(PointAffine::new(x.clone(),y.negate(),a.clone(),b.clone()));
+                }
+                points.push(PointAffine::new(x,y,a.clone(),b.clone()));
+            }
+        }
+        points.sort_by(|p1,p2| (p1.get_x().get_number(),p1.get_y().get_number()).cmp(&(p2.get_x().get_number(),p2.get_y().get_number())));
+        points.insert(0,PointAffine::identity(a,b));
+        Ok(points)
+    }
+
+}
+
+
+// Distinct roots of f in its coefficient field GF(q), q odd: gcd with x^q - x
+// keeps the linear factors and Cantor-Zassenhaus splits them apart with
+// gcd(g, (x + d)^((q - 1) / 2) - 1) for sampled d.
+fn roots<F: Field>(f:&Polynomial<F>, mut sampler:impl FnMut() -> F) -> Vec<F>{
+    let Some(lead) = f.leading_coefficient().filter(|_| f.degree() > Some(0)) else {
+        return vec![];
+    };
+    let one = Polynomial::constant(lead.one_like());
+    let q = lead.order();
+    let x = Polynomial::x_like(lead);
+    let linear = f.gcd(&(&x.pow_mod(&q,f) - &x));
+
+    let half = (q - 1) >> 1;
+    let mut pending = vec![linear];
+    let mut found = vec![];
+    while let Some(g) = pending.pop(){
+        match g.degree(){
+            None | Some(0) => {},
+            Some(1) => {
+                let c = g.get_coefficients();
+                found.push(c[0].negate() / &c[1]);
+            },
+            Some(d) => {
+                let shifted = &x + &Polynomial::constant(sampler());
+                let h = g.gcd(&(&shifted.pow_mod(&half,&g) - &one));
+                if h.degree().is_some_and(|e| e > 0 && e < d){
+                    pending.push(g.div_rem(&h).0);
+                    pending.push(h);
+                }
+                else{
+                    pending.push(g);
+                }
+            },
+        }
+    }
+    found
+}
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::small_curve;
+
+
+#[test]
+fn rational_torsion_matches_enumeration(){
+    for curve in [small_curve(2,3,97),small_curve(4,6,11),small_curve(0,1,31),small_curve(1,1,23),small_curve(1,0,11),small_curve(2,0,13)]{
+        for n in 1..=12{
+            let mut expected: Vec<_> = curve.points().filter(|point| point.multiply(&BigInt::from(n)).is_identity()).collect();
+            expected.sort_by(|p1,p2| (p1.is_identity(),p1.get_x().get_number(),p1.get_y().get_number()).cmp(&(p2.is_identity(),p2.get_x().get_number(),p2.get_y().get_number())));
+            expected.rotate_right(1);
+            assert_eq!(curve.torsion_points(n).unwrap(),expected);
+        }
+    }
+}
+
+
+#[test]
+fn rational_torsion_structure(){
+    // y^2 = x^3 + 1 over GF(31) is Z/6 x Z/6
+    let curve = small_curve(0,1,31);
+    assert_eq!(curve.torsion_structure(6).unwrap(),GroupStructure::new(BigInt::from(6),BigInt::from(6)));
+    assert_eq!(curve.torsion_structure(4).unwrap(),GroupStructure::new(BigInt::from(2),BigInt::from(2)));
+    assert!(curve.has_full_torsion(6).unwrap());
+    assert!(curve.has_full_torsion(3).unwrap());
+    assert!(!curve.has_full_torsion(4).unwrap());
+    assert_eq!(curve.two_torsion_x().len(),3);
+
+    // x^3 + x = x (x^2 + 1) and -1 is not a square mod 11
+    assert_eq!(small_curve(1,0,11).two_torsion_x(),vec![FieldElement::zero(BigInt::from(11))]);
+
+    let curve = small_curve(4,6,11);
+    assert_eq!(curve.torsion_structure(8).unwrap(),GroupStructure::new(BigInt::from(8),BigInt::from(2)));
+    assert_eq!(curve.torsion_structure(3).unwrap(),GroupStructure::new(BigInt::one(),BigInt::one()));
+}
+
+
+#[test]
+fn torsion_over_extension_fields(){
+    // y^2 = x^3 + 1 over GF(5) is supersingular with 6 points, and over GF(25)
+    // it has 36 points forming Z/6 x Z/6
+    let curve = small_curve(0,1,5);
+    let field = ExtensionField::with_degree(BigInt::from(5),2).unwrap();
+    assert_eq!(curve.torsion_points(3).unwrap().len(),3);
+    for (n,count) in [(2,4),(3,9),(6,36),(4,4)]{
+        let points = curve.torsion_points_over(&field,n).unwrap();
+        assert_eq!(points.len(),count);
+        for point in points.iter(){
+            assert!(point.is_on_curve());
+            assert!(point.multiply(&BigInt::from(n)).is_identity());
+        }
+    }
+    assert_eq!(curve.full_torsion_degree(6,10).unwrap(),Some(2));
+    assert!(curve.torsion_points_over(&ExtensionField::with_degree(BigInt::from(7),2).unwrap(),2).is_err());
+}
+
+
+#[test]
+fn full_torsion_needs_the_embedding_degree(){
+    // 5 points over GF(7) and 7 has order 4 mod 5, so E[5] shows up over GF(7^4)
+    let curve = small_curve(1,1,7);
+    assert_eq!(curve.embedding_degree(&BigInt::from(5),10),Some(4));
+    assert_eq!(curve.full_torsion_degree(5,8).unwrap(),Some(4));
+    assert_eq!(curve.full_torsion_degree(5,3).unwrap(),None);
+}
+
+
+#[test]
+fn embedding_degrees(){
+    use crate::elliptic_curve::curves::secp256k1;
+
+    // supersingular curves over p = 2 mod 3 have embedding degree 2, 10008 = 2^3 3^2 139
+    let curve = small_curve(0,1,10007);
+    assert_eq!(curve.order().unwrap(),BigInt::from(10008));
+    assert_eq!(curve.embedding_degree(&BigInt::from(139),100),Some(2));
+    assert_eq!(curve.embedding_degree(&BigInt::from(10007),100),None);
+
+    let curve = secp256k1();
+    assert_eq!(curve.embedding_degree(curve.get_order().unwrap(),1000),None);
+}
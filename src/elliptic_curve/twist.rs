@@ -0,0 +1,307 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive, Zero};
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::{Field, FieldElementTraits};
+use crate::elliptic_curve::extension_field::{ExtensionField, ExtensionFieldElement};
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::elliptic_curve::number_theory::{partial_factor, RHO_BUDGET};
+
+
+// Quadratic twist E^d: y^2 = x^3 + a d^2 x + b d^3 of E: y^2 = x^3 + ax + b for
+// a non-residue d. The two curves become isomorphic over Fp2 = Fp(s), s^2 = d,
+// through (x, y) -> (d x, d s y), and #E(Fp) + #E^d(Fp) = 2p + 2. Every x in Fp
+// is the x coordinate of a point of E or of E^d, so an x-only ladder fed a
+// value off E computes on E^d, and the twist order has to be as hard as #E.
+#[derive(Debug,Clone,PartialEq)]
+pub struct QuadraticTwist{
+    curve: EllipticCurve,
+    twist: EllipticCurve,
+    d: FieldElement,
+    field: ExtensionField,
+}
+
+
+// #E^d(Fp) and its factorisation, for judging the twist against invalid
+// curve attacks on x-only protocols. Composites Pollard rho could not split
+// within RHO_BUDGET steps are kept together in `unfactored`.
+#[derive(Debug,Clone,PartialEq)]
+pub struct TwistReport{
+    order: BigInt,
+    twist_order: BigInt,
+    factors: Vec<(BigInt,u32)>,
+    unfactored: BigInt,
+}
+
+
+impl EllipticCurve{
+
+    // E^d for a non-residue d, without generator or other metadata
+    pub fn quadratic_twist(&self, d:&FieldElement) -> Result<EllipticCurve,FieldErrors>{
+        if d.get_prime() != self.get_prime(){
+            return Err(FieldErrors::Mismatch("Twisting element is in a different field".to_string()));
+        }
+        if d.is_zero() || d.is_square(){
+            return Err(FieldErrors::InvalidParams("A quadratic twist needs a non-residue, squares give an isomorphic curve".to_string()));
+        }
+        let d2 = d.square();
+        EllipticCurve::new(self.get_a().clone() * &d2,self.get_b().clone() * d2 * d)
+    }
+
+    // Twist by the smallest non-residue
+    pub fn twist(&self) -> Result<QuadraticTwist,FieldErrors>{
+        let d = self.get_a().non_residue()
+            .ok_or(FieldErrors::InvalidParams("GF(2) has no non-residue".to_string()))?;
+        QuadraticTwist::new(self,d)
+    }
+
+    // #E^d = 2p + 2 - #E, so this needs #E: declared, or counted for fields
+    // up to about 64 bits (see counting::schoof)
+    pub fn twist_report(&self) -> Result<TwistReport,FieldErrors>{
+        let order = self.group_order()?;
+        let twist_order = self.get_prime() * 2 + 2 - &order;
+        let (factors,unfactored) = partial_factor(&twist_order,RHO_BUDGET);
+        Ok(TwistReport { order, twist_order, factors, unfactored })
+    }
+
+}
+
+
+impl QuadraticTwist{
+
+    pub fn new(curve:&EllipticCurve, d:FieldElement) -> Result<QuadraticTwist,FieldErrors>{
+        let twist = curve.quadratic_twist(&d)?;
+        let p = curve.get_prime().clone();
+        // s^2 - d is irreducible since d is not a square
+        let field = ExtensionField::new(p.clone(),vec![&p - d.get_number(),BigInt::zero(),BigInt::one()])?;
+        Ok(QuadraticTwist { curve: curve.clone(), twist, d, field })
+    }
+
+    pub fn get_curve(&self) -> &EllipticCurve{
+        &self.curve
+    }
+
+    pub fn get_twist(&self) -> &EllipticCurve{
+        &self.twist
+    }
+
+    pub fn get_d(&self) -> &FieldElement{
+        &self.d
+    }
+
+    // Fp2 = Fp[s] / (s^2 - d)
+    pub fn get_field(&self) -> &ExtensionField{
+        &self.field
+    }
+
+    pub fn sqrt_d(&self) -> ExtensionFieldElement{
+        self.field.element(vec![BigInt::zero(),BigInt::one()])
+    }
+
+    // A point of E(Fp) or E^d(Fp) as a point over Fp2
+    pub fn lift(&self, point:&PointAffine<FieldElement>) -> PointAffine<ExtensionFieldElement>{
+        let lift = |c:&FieldElement| self.field.element(vec![c.get_number().clone()]);
+        let (a,b) = (lift(point.get_a()),lift(point.get_b()));
+        if point.is_identity(){
+            return PointAffine::identity(a,b);
+        }
+        PointAffine::new(lift(point.get_x()),lift(point.get_y()),a,b)
+    }
+
+    // The point over Fp when both coordinates lie in Fp
+    pub fn descend(&self, point:&PointAffine<ExtensionFieldElement>) -> Option<PointAffine<FieldElement>>{
+        let p = self.field.get_prime();
+        let coordinates = [point.get_x(),point.get_y(),point.get_a(),point.get_b()];
+        if coordinates.iter().any(|c| c.get_number() >= p){
+            return None;
+        }
+        let [x,y,a,b] = coordinates.map(|c| FieldElement::new(c.get_number().clone(),p.clone()).unwrap());
+        if point.is_identity(){
+            return Some(PointAffine::identity(a,b));
+        }
+        Some(PointAffine::new(x,y,a,b))
+    }
+
+    // E(Fp2) -> E^d(Fp2), (x, y) -> (d x, d s y)
+    pub fn to_twist(&self, point:&PointAffine<ExtensionFieldElement>) -> PointAffine<ExtensionFieldElement>{
+        let d = self.field.element(vec![self.d.get_number().clone()]);
+        let (a,b) = (self.lift_element(self.twist.get_a()),self.lift_element(self.twist.get_b()));
+        if point.is_identity(){
+            return PointAffine::identity(a,b);
+        }
+        let ds = d.clone() * self.sqrt_d();
+        PointAffine::new(d * point.get_x(),ds * point.get_y(),a,b)
+    }
+
+    // E^d(Fp2) -> E(Fp2), (x, y) -> (x / d, y / d s)
+    pub fn from_twist(&self, point:&PointAffine<ExtensionFieldElement>) -> PointAffine<ExtensionFieldElement>{
+        let d = self.field.element(vec![self.d.get_number().clone()]);
+        let (a,b) = (self.lift_element(self.curve.get_a()),self.lift_element(self.curve.get_b()));
+        if point.is_identity(){
+            return PointAffine::identity(a,b);
+        }
+        let ds = d.clone() * self.sqrt_d();
+        PointAffine::new(point.get_x().clone() / d,point.get_y().clone() / ds,a,b)
+    }
+
+    fn lift_element(&self, c:&FieldElement) -> ExtensionFieldElement{
+        self.field.element(vec![c.get_number().clone()])
+    }
+
+}
+
+
+impl TwistReport{
+
+    pub fn get_order(&self) -> &BigInt{
+        &self.order
+    }
+
+    pub fn get_twist_order(&self) -> &BigInt{
+        &self.twist_order
+    }
+
+    pub fn get_factors(&self) -> &Vec<(BigInt,u32)>{
+        &self.factors
+    }
+
+    pub fn get_unfactored(&self) -> &BigInt{
+        &self.unfactored
+    }
+
+    pub fn is_fully_factored(&self) -> bool{
+        self.unfactored.is_one()
+    }
+
+    // None when a composite was left over, its prime factors could be larger
+    pub fn largest_prime_factor(&self) -> Option<BigInt>{
+        if !self.is_fully_factored(){
+            return None;
+        }
+        Some(self.factors.iter().map(|(q,_)| q.clone()).max().unwrap_or(BigInt::one()))
+    }
+
+    // log2 of the 0.886 sqrt(l) group operations Pollard rho needs on the
+    // largest prime order subgroup l of the twist
+    pub fn rho_security_bits(&self) -> Option<f64>{
+        self.largest_prime_factor().map(|l| rho_bits(&l))
+    }
+
+    // Upper bound on `rho_security_bits`, using the leftover composite as if it
+    // were prime
+    pub fn rho_security_bound(&self) -> f64{
+        let largest = self.factors.iter().map(|(q,_)| q.clone()).max().unwrap_or(BigInt::one());
+        rho_bits(&largest.max(self.unfactored.clone()))
+    }
+
+}
+
+
+impl fmt::Display for TwistReport{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result{
+        let mut factors: Vec<String> = self.factors.iter()
+            .map(|(q,e)| if *e == 1 { q.to_string() } else { format!("{}^{}",q,e) })
+            .collect();
+        match self.rho_security_bits(){
+            Some(bits) => write!(f,"#E' = {} = {}, rho security {:.1} bits",self.twist_order,factors.join(" * "),bits),
+            None => {
+                factors.push(format!("{} (composite)",self.unfactored));
+                write!(f,"#E' = {} = {}, rho security at most {:.1} bits",self.twist_order,factors.join(" * "),self.rho_security_bound())
+            },
+        }
+    }
+}
+
+
+// log2(sqrt(pi l / 4)), shifting l down first so it fits in an f64
+pub fn rho_bits(l:&BigInt) -> f64{
+    let shift = l.bits().saturating_sub(64);
+    let top = (l >> shift).to_f64().unwrap();
+    (top.log2() + shift as f64 + (std::f64::consts::PI / 4.0).log2()) / 2.0
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::small_curve;
+
+
+#[test]
+fn twist_orders_add_up(){
+    for curve in [small_curve(2,3,97),small_curve(4,6,11),small_curve(1,1,1009),small_curve(5,7,10007)]{
+        let p = curve.get_prime().clone();
+        let twist = curve.twist().unwrap();
+        assert_eq!(curve.order().unwrap() + twist.get_twist().order().unwrap(),&p * 2 + 2);
+
+        // any non-residue gives a twist with the same number of points
+        let mut d = FieldElement::new(BigInt::from(2),p.clone()).unwrap();
+        let mut count = 0;
+        while count < 3 && d.get_number() < &p{
+            if !d.is_square(){
+                assert_eq!(curve.quadratic_twist(&d).unwrap().order().unwrap(),twist.get_twist().order().unwrap());
+                count += 1;
+            }
+            d = d + FieldElement::one(p.clone());
+        }
+        assert!(curve.quadratic_twist(&FieldElement::new(BigInt::from(4),p.clone()).unwrap()).is_err());
+        assert!(curve.quadratic_twist(&FieldElement::zero(p)).is_err());
+    }
+}
+
+
+#[test]
+fn twist_point_maps(){
+    let curve = small_curve(2,3,97);
+    let twist = curve.twist().unwrap();
+    let s = twist.sqrt_d();
+    assert_eq!(s.clone() * &s,twist.get_field().element(vec![twist.get_d().get_number().clone()]));
+
+    for point in curve.points(){
+        let lifted = twist.lift(&point);
+        let image = twist.to_twist(&lifted);
+        assert!(image.is_on_curve());
+        assert_eq!(twist.from_twist(&image),lifted);
+        // x stays in Fp, so only 2-torsion and O survive descending to E^d(Fp)
+        assert_eq!(twist.descend(&image).is_some(),point.is_identity() || point.get_y().get_number().is_zero());
+    }
+
+    let points: Vec<_> = twist.get_twist().points().collect();
+    for (i,point) in points.iter().enumerate(){
+        let image = twist.from_twist(&twist.lift(point));
+        assert!(image.is_on_curve());
+        // the isomorphism respects the group law
+        let other = &points[(i * 7 + 3) % points.len()];
+        let sum = twist.from_twist(&twist.lift(&point.add(other.clone())));
+        assert_eq!(sum,image.add(twist.from_twist(&twist.lift(other))));
+    }
+}
+
+
+#[test]
+fn twist_reports(){
+    use crate::elliptic_curve::curves::secp256k1;
+    use num_traits::Num;
+
+    let report = small_curve(2,3,97).twist_report().unwrap();
+    assert_eq!(report.get_order() + report.get_twist_order(),BigInt::from(196));
+    let product = report.get_factors().iter().fold(BigInt::one(),|acc,(q,e)| acc * q.pow(*e));
+    assert_eq!(&product,report.get_twist_order());
+
+    // secp256k1's twist has order 3^2 13^2 3319 22639 l for a 220 bit prime l
+    let report = secp256k1().twist_report().unwrap();
+    let small: Vec<_> = report.get_factors().iter().take(4).cloned().collect();
+    assert_eq!(small,[(BigInt::from(3),2),(BigInt::from(13),2),(BigInt::from(3319),1),(BigInt::from(22639),1)]);
+    let l = BigInt::from_str_radix("1013176677300131846900870239606035638738100997248092069256697437031",10).unwrap();
+    assert!(report.is_fully_factored());
+    assert_eq!(report.largest_prime_factor(),Some(l));
+    assert!((report.rho_security_bits().unwrap() - 109.3).abs() < 0.5);
+    assert!(report.to_string().starts_with(&format!("#E' = {} = 3^2 * 13^2 * 3319 * 22639 * ",report.get_twist_order())));
+}
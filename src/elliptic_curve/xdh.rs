@@ -0,0 +1,172 @@
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::montgomery::MontgomeryCurve;
+
+
+// X25519 and X448 of RFC 7748. Scalars and u coordinates are little endian
+// byte strings, scalars are clamped before use and non canonical u values are
+// reduced modulo p.
+pub const X25519_BASE_POINT: [u8;32] = base_point::<32>(9);
+pub const X448_BASE_POINT: [u8;56] = base_point::<56>(5);
+
+
+const fn base_point<const N: usize>(u:u8) -> [u8;N]{
+    let mut bytes = [0_u8;N];
+    bytes[0] = u;
+    bytes
+}
+
+
+// p = 2^255 - 19, v^2 = u^3 + 486662 u^2 + u
+pub fn curve25519() -> MontgomeryCurve<FieldElement>{
+    let p = (BigInt::one() << 255) - BigInt::from(19);
+    montgomery_curve(p,486662)
+}
+
+
+// p = 2^448 - 2^224 - 1, v^2 = u^3 + 156326 u^2 + u
+pub fn curve448() -> MontgomeryCurve<FieldElement>{
+    let p = (BigInt::one() << 448) - (BigInt::one() << 224) - BigInt::one();
+    montgomery_curve(p,156326)
+}
+
+
+fn montgomery_curve(p:BigInt, a:u32) -> MontgomeryCurve<FieldElement>{
+    let a = FieldElement::new(BigInt::from(a),p.clone()).unwrap();
+    let b = FieldElement::one(p);
+    MontgomeryCurve::new(a,b).unwrap()
+}
+
+
+// decodeScalar25519: clear the three low bits and bit 255, set bit 254
+pub fn clamp_scalar_25519(k:&[u8;32]) -> BigInt{
+    let mut k = *k;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    BigInt::from_bytes_le(Sign::Plus,&k)
+}
+
+
+// decodeScalar448: clear the two low bits, set bit 447
+pub fn clamp_scalar_448(k:&[u8;56]) -> BigInt{
+    let mut k = *k;
+    k[0] &= 252;
+    k[55] |= 128;
+    BigInt::from_bytes_le(Sign::Plus,&k)
+}
+
+
+pub fn x25519(k:&[u8;32], u:&[u8;32]) -> [u8;32]{
+    // the most significant bit of the u coordinate is ignored
+    let mut u = *u;
+    u[31] &= 127;
+    xdh(&curve25519(),&clamp_scalar_25519(k),&u,255)
+}
+
+
+pub fn x448(k:&[u8;56], u:&[u8;56]) -> [u8;56]{
+    xdh(&curve448(),&clamp_scalar_448(k),u,448)
+}
+
+
+fn xdh<const N: usize>(curve:&MontgomeryCurve<FieldElement>, k:&BigInt, u:&[u8;N], bits:u64) -> [u8;N]{
+    let p = curve.get_a().get_prime();
+    let u = FieldElement::new(BigInt::from_bytes_le(Sign::Plus,u) % p,p.clone()).unwrap();
+    let result = curve.ladder(k,&u,bits);
+
+    let (_,bytes) = result.get_number().to_bytes_le();
+    let mut out = [0_u8;N];
+    out[..bytes.len()].copy_from_slice(&bytes);
+    out
+}
+
+
+
+
+#[cfg(test)]
+fn hex_bytes<const N: usize>(hex:&str) -> [u8;N]{
+    let mut bytes = [0_u8;N];
+    for (i,byte) in bytes.iter_mut().enumerate(){
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2],16).unwrap();
+    }
+    bytes
+}
+
+
+#[test]
+fn x25519_test_vectors(){
+    // RFC 7748 section 5.2
+    let k = hex_bytes("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4");
+    let u = hex_bytes("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c");
+    assert_eq!(x25519(&k,&u),hex_bytes("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"));
+
+    let k = hex_bytes("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d");
+    let u = hex_bytes("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493");
+    assert_eq!(x25519(&k,&u),hex_bytes("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"));
+}
+
+
+#[test]
+fn x448_test_vectors(){
+    // RFC 7748 section 5.2
+    let k = hex_bytes("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3");
+    let u = hex_bytes("06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086");
+    assert_eq!(x448(&k,&u),hex_bytes("ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f"));
+
+    let k = hex_bytes("203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f");
+    let u = hex_bytes("0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db");
+    assert_eq!(x448(&k,&u),hex_bytes("884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d"));
+}
+
+
+#[test]
+fn x25519_diffie_hellman(){
+    // RFC 7748 section 6.1
+    let alice = hex_bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+    let bob = hex_bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+
+    let alice_public = x25519(&alice,&X25519_BASE_POINT);
+    let bob_public = x25519(&bob,&X25519_BASE_POINT);
+    assert_eq!(alice_public,hex_bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+    assert_eq!(bob_public,hex_bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+
+    let shared = hex_bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    assert_eq!(x25519(&alice,&bob_public),shared);
+    assert_eq!(x25519(&bob,&alice_public),shared);
+}
+
+
+#[test]
+fn x25519_iterated(){
+    // RFC 7748 section 5.2: k = u = 9, then k <- X25519(k, u), u <- old k
+    let mut k = X25519_BASE_POINT;
+    let mut u = X25519_BASE_POINT;
+    for i in 0..1000{
+        let next = x25519(&k,&u);
+        u = k;
+        k = next;
+        if i == 0{
+            assert_eq!(k,hex_bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"));
+        }
+    }
+    assert_eq!(k,hex_bytes("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"));
+}
+
+
+#[test]
+fn x448_iterated(){
+    let mut k = X448_BASE_POINT;
+    let mut u = X448_BASE_POINT;
+    for i in 0..1000{
+        let next = x448(&k,&u);
+        u = k;
+        k = next;
+        if i == 0{
+            assert_eq!(k,hex_bytes("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"));
+        }
+    }
+    assert_eq!(k,hex_bytes("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"));
+}
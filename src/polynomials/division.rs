@@ -0,0 +1,264 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+use crate::elliptic_curve::field::FieldElement;
+use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::errors::FieldErrors;
+use crate::elliptic_curve::curve::EllipticCurve;
+use crate::elliptic_curve::point::point::PointAffine;
+use crate::polynomials::polynomial::Polynomial;
+
+
+type Poly = Polynomial<FieldElement>;
+
+
+// Division polynomials of y^2 = x^3 + ax + b up to a chosen index. psi_n has a
+// factor y exactly when n is even, so the table keeps the x-polynomials
+// f_n = psi_n for odd n and f_n = psi_n / 2y for even n, which satisfy
+//
+//   f_2m+1 = 16 F^2 f_m+2 f_m^3 - f_m-1 f_m+1^3    (m even)
+//   f_2m+1 = f_m+2 f_m^3 - 16 F^2 f_m-1 f_m+1^3    (m odd)
+//   f_2m   = f_m (f_m+2 f_m-1^2 - f_m-2 f_m+1^2)
+//
+// with F = x^3 + ax + b standing in for y^2.
+#[derive(Debug,Clone)]
+pub struct DivisionPolynomials{
+    curve: EllipticCurve,
+    rhs: Poly,
+    f: Vec<Poly>,
+}
+
+
+// [n](x, y) = (x_numerator / x_denominator, y y_numerator / y_denominator)
+#[derive(Debug,Clone,PartialEq)]
+pub struct MultiplicationMap{
+    n: usize,
+    x_numerator: Poly,
+    x_denominator: Poly,
+    y_numerator: Poly,
+    y_denominator: Poly,
+}
+
+
+impl DivisionPolynomials{
+
+    // f_0 .. f_n
+    pub fn new(curve:&EllipticCurve, n:usize) -> Self{
+        let p = curve.get_prime();
+        let (a,b) = (curve.get_a().get_number(),curve.get_b().get_number());
+        let integers = |coefficients:Vec<BigInt>| Polynomial::from_integers(coefficients,p);
+
+        let rhs = curve_polynomial(curve);
+        let sixteen_rhs_squared = (&rhs * &rhs).scale(&FieldElement::new(BigInt::from(16) % p,p.clone()).unwrap());
+
+        let mut f = vec![
+            Polynomial::zero(),
+            integers(vec![BigInt::from(1)]),
+            integers(vec![BigInt::from(1)]),
+            integers(vec![-(a * a),b * 12,a * 6,BigInt::zero(),BigInt::from(3)]),
+            integers(vec![(-(b * b) * 8 - a * a * a) * 2,-(a * b) * 8,-(a * a) * 10,b * 40,a * 10,BigInt::zero(),BigInt::from(2)]),
+        ];
+        for i in 5..=n{
+            let m = i / 2;
+            let next = if i % 2 == 1{
+                let (first,second) = (&(&f[m + 2] * &f[m]) * &(&f[m] * &f[m]),&(&f[m - 1] * &f[m + 1]) * &(&f[m + 1] * &f[m + 1]));
+                if m % 2 == 0 { &(&sixteen_rhs_squared * &first) - &second } else { &first - &(&sixteen_rhs_squared * &second) }
+            }
+            else{
+                let first = &f[m + 2] * &(&f[m - 1] * &f[m - 1]);
+                let second = &f[m - 2] * &(&f[m + 1] * &f[m + 1]);
+                &f[m] * &(&first - &second)
+            };
+            f.push(next);
+        }
+        f.truncate(n + 1);
+        DivisionPolynomials { curve: curve.clone(), rhs, f }
+    }
+
+    pub fn get_curve(&self) -> &EllipticCurve{
+        &self.curve
+    }
+
+    // largest n in the table
+    pub fn get_bound(&self) -> usize{
+        self.f.len() - 1
+    }
+
+    // f_n, that is psi_n for odd n and psi_n / 2y for even n
+    pub fn psi(&self, n:usize) -> Result<&Poly,FieldErrors>{
+        self.f.get(n).ok_or(FieldErrors::InvalidParams(format!("Division polynomials were generated up to {}",self.get_bound())))
+    }
+
+    // psi_n^2 as a polynomial in x, 4F f_n^2 for even n
+    pub fn psi_squared(&self, n:usize) -> Result<Poly,FieldErrors>{
+        let f = self.psi(n)?;
+        let square = f * f;
+        if n % 2 == 1{
+            return Ok(square);
+        }
+        Ok((&self.rhs * &square).scale(&self.integer(4)))
+    }
+
+    // Monic polynomial whose roots are the x coordinates of the points of
+    // order dividing n other than O: psi_n for odd n and F f_n for even n,
+    // where F adds the points of order two.
+    pub fn torsion_polynomial(&self, n:usize) -> Result<Poly,FieldErrors>{
+        if n == 0{
+            return Err(FieldErrors::InvalidParams("Every point has order dividing 0".to_string()));
+        }
+        let f = self.psi(n)?;
+        if n % 2 == 1{
+            return Ok(f.monic());
+        }
+        Ok((&self.rhs * f).monic())
+    }
+
+    // [n](x, y) = (x - psi_n-1 psi_n+1 / psi_n^2, psi_2n / 2 psi_n^4), needs
+    // the table to reach 2n
+    pub fn multiplication_map(&self, n:usize) -> Result<MultiplicationMap,FieldErrors>{
+        if n == 0{
+            return Err(FieldErrors::InvalidParams("[0] sends every point to O".to_string()));
+        }
+        let x = Polynomial::x(self.curve.get_prime());
+        let psi_n_squared = self.psi_squared(n)?;
+        let neighbours = self.psi(n - 1)? * self.psi(n + 1)?;
+        // psi_n-1 psi_n+1 carries y^2 = F when n is odd
+        let neighbours = if n % 2 == 1 { (&self.rhs * &neighbours).scale(&self.integer(4)) } else { neighbours };
+        let x_numerator = &(&x * &psi_n_squared) - &neighbours;
+
+        // psi_2n / 2 = y f_2n
+        let y_numerator = self.psi(2 * n)?.clone();
+        let y_denominator = &psi_n_squared * &psi_n_squared;
+
+        Ok(MultiplicationMap { n, x_numerator, x_denominator: psi_n_squared, y_numerator, y_denominator })
+    }
+
+    fn integer(&self, n:i64) -> FieldElement{
+        let p = self.curve.get_prime();
+        FieldElement::new(((BigInt::from(n) % p) + p) % p,p.clone()).unwrap()
+    }
+
+}
+
+
+impl MultiplicationMap{
+
+    pub fn get_n(&self) -> usize{
+        self.n
+    }
+
+    pub fn get_x_numerator(&self) -> &Poly{
+        &self.x_numerator
+    }
+
+    pub fn get_x_denominator(&self) -> &Poly{
+        &self.x_denominator
+    }
+
+    pub fn get_y_numerator(&self) -> &Poly{
+        &self.y_numerator
+    }
+
+    pub fn get_y_denominator(&self) -> &Poly{
+        &self.y_denominator
+    }
+
+    // [n] point by evaluating the map, O where psi_n vanishes
+    pub fn apply(&self, point:&PointAffine<FieldElement>) -> PointAffine<FieldElement>{
+        let identity = PointAffine::identity(point.get_a().clone(),point.get_b().clone());
+        if point.is_identity(){
+            return identity;
+        }
+        let x = point.get_x();
+        let x_denominator = self.x_denominator.evaluate(x.clone());
+        if x_denominator.get_number().is_zero(){
+            return identity;
+        }
+        let x_n = self.x_numerator.evaluate(x.clone()) / x_denominator;
+        let y_n = point.get_y() * &(self.y_numerator.evaluate(x.clone()) / self.y_denominator.evaluate(x.clone()));
+        PointAffine::new(x_n,y_n,point.get_a().clone(),point.get_b().clone())
+    }
+
+}
+
+
+// x^3 + ax + b
+pub fn curve_polynomial(curve:&EllipticCurve) -> Poly{
+    let p = curve.get_prime();
+    Polynomial::new(vec![curve.get_b().clone(),curve.get_a().clone(),FieldElement::zero(p.clone()),FieldElement::one(p.clone())])
+}
+
+
+
+
+#[cfg(test)]
+use crate::elliptic_curve::test_helpers::small_curve;
+
+
+#[test]
+fn division_polynomial_degrees(){
+    let division = DivisionPolynomials::new(&small_curve(2,3,97),12);
+    assert_eq!(division.get_bound(),12);
+    for n in 1..=12{
+        // (n^2 - 1) / 2 for odd n and (n^2 - 4) / 2 for even n
+        let degree = if n % 2 == 1 { (n * n - 1) / 2 } else { (n * n - 4) / 2 };
+        assert_eq!(division.psi(n).unwrap().degree(),Some(degree));
+        let torsion = division.torsion_polynomial(n).unwrap();
+        assert_eq!(torsion.degree(),Some(if n % 2 == 1 { degree } else { degree + 3 }));
+        assert_eq!(torsion.leading_coefficient().unwrap(),&FieldElement::one(BigInt::from(97)));
+    }
+    assert!(division.psi(13).is_err());
+    assert!(division.multiplication_map(7).is_err());
+}
+
+
+#[test]
+fn psi_three_and_doubling_formula(){
+    let p = BigInt::from(97);
+    let coefficients = |c:&[i64]| Polynomial::from_integers(c.iter().map(|&c| BigInt::from(c)).collect(),&p);
+    let (a,b) = (2_i64,3_i64);
+    let division = DivisionPolynomials::new(&small_curve(2,3,97),4);
+
+    // psi_3 = 3x^4 + 6ax^2 + 12bx - a^2
+    assert_eq!(division.psi(3).unwrap(),&coefficients(&[-a * a,12 * b,6 * a,0,3]));
+
+    // x([2] P) = (x^4 - 2ax^2 - 8bx + a^2) / 4 (x^3 + ax + b)
+    let doubling = division.multiplication_map(2).unwrap();
+    assert_eq!(doubling.get_x_numerator(),&coefficients(&[a * a,-8 * b,-2 * a,0,1]));
+    assert_eq!(doubling.get_x_denominator(),&coefficients(&[4 * b,4 * a,0,4]));
+}
+
+
+#[test]
+fn torsion_polynomial_roots(){
+    use crate::elliptic_curve::field_traits::Field;
+    use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+    // x^3 + 4x + 6 splits over GF(11), so the curve has all three points of order two
+    for curve in [small_curve(2,3,97),small_curve(4,6,11)]{
+        let division = DivisionPolynomials::new(&curve,8);
+        for point in curve.points().skip(1){
+            for n in 1..=8{
+                let killed = point.multiply(&BigInt::from(n)).is_identity();
+                let root = division.torsion_polynomial(n).unwrap().evaluate(point.get_x().clone()).is_zero();
+                assert_eq!(killed,root);
+            }
+        }
+    }
+}
+
+
+#[test]
+fn multiplication_map_matches_scalar_multiplication(){
+    use crate::elliptic_curve::point::pointTraits::PointOperations;
+
+    for curve in [small_curve(2,3,97),small_curve(4,6,11),small_curve(0,3,7)]{
+        let division = DivisionPolynomials::new(&curve,12);
+        for n in 1..=6{
+            let map = division.multiplication_map(n).unwrap();
+            assert_eq!(map.get_n(),n);
+            for point in curve.points(){
+                assert_eq!(map.apply(&point),point.multiply(&BigInt::from(n)));
+            }
+        }
+    }
+}
@@ -1 +1,2 @@
 pub mod polynomial;
+pub mod division;
\ No newline at end of file
@@ -1,45 +1,103 @@
 use num_bigint::BigInt;
-use num_traits::Zero;
+use num_traits::Signed;
 use crate::elliptic_curve::field::FieldElement;
-use crate::elliptic_curve::field_traits::FieldElementTraits;
+use crate::elliptic_curve::field_traits::Field;
 
-use std::ops::{Add};
-use std::fmt::{self, format};
+use std::ops::{Add,Sub,Mul};
+use std::fmt;
 use std::cmp::max;
 
+// Coefficients from the constant term up, without trailing zeros so that the
+// zero polynomial is the empty vector. Works over any `Field`: GF(p) for
+// division polynomials and Schoof, GF(p^k) for roots in extensions.
 #[derive(PartialEq,Debug,Clone)]
-pub struct Polynomial<FieldElement>{
-    coefficients:Vec<FieldElement>
+pub struct Polynomial<F>{
+    coefficients:Vec<F>
 }
 
 
-impl Add for Polynomial<FieldElement>{
-    type Output = Polynomial<FieldElement>;
+impl<F: Field> Add for Polynomial<F>{
+    type Output = Polynomial<F>;
 
-    fn add(self,other:Polynomial<FieldElement>) -> Self{
+    fn add(self,other:Polynomial<F>) -> Self{
+        &self + &other
+    }
+}
+
+impl<'a, F: Field> Add<&'a Polynomial<F>> for &'a Polynomial<F>{
+    type Output = Polynomial<F>;
+
+    fn add(self,other:&Polynomial<F>) -> Self::Output{
 
         let l1 = self.coefficients.len();
         let l2 = other.coefficients.len();
         let max_length = max(l1,l2);
 
-        let first_poly = &self.coefficients;
+        let mut result = Vec::with_capacity(max_length);
+        for i in 0..max_length{
+            let c = match (self.coefficients.get(i),other.coefficients.get(i)){
+                (Some(c1),Some(c2)) => c1.clone() + c2,
+                (Some(c),None) | (None,Some(c)) => c.clone(),
+                (None,None) => unreachable!(),
+            };
+            result.push(c);
+        }
+        Polynomial::new(result)
+
+    }
+}
 
-        let mut result = Polynomial{coefficients:vec![FieldElement::new(BigInt::zero(),first_poly[0].get_prime().clone()).unwrap();max_length]};
+impl<F: Field> Sub for Polynomial<F>{
+    type Output = Polynomial<F>;
 
-        for i in 0..l1{
-            result.coefficients[i] = (result.coefficients[i].clone() + self.coefficients[i].clone()); 
-        }
+    fn sub(self,other:Polynomial<F>) -> Self{
+        &self - &other
+    }
+}
+
+impl<'a, F: Field> Sub<&'a Polynomial<F>> for &'a Polynomial<F>{
+    type Output = Polynomial<F>;
 
-        for i in 0..l2{
-            result.coefficients[i] = (&result.coefficients[i] + &other.coefficients[i]); 
+    fn sub(self,other:&Polynomial<F>) -> Self::Output{
+
+        let max_length = max(self.coefficients.len(),other.coefficients.len());
+
+        let mut result = Vec::with_capacity(max_length);
+        for i in 0..max_length{
+            let c = match (self.coefficients.get(i),other.coefficients.get(i)){
+                (Some(c1),Some(c2)) => c1.clone() - c2,
+                (Some(c),None) => c.clone(),
+                (None,Some(c)) => c.negate(),
+                (None,None) => unreachable!(),
+            };
+            result.push(c);
         }
+        Polynomial::new(result)
 
-        result
+    }
+}
+
+impl<F: Field> Mul for Polynomial<F>{
+    type Output = Polynomial<F>;
 
+    fn mul(self,other:Polynomial<F>) -> Self{
+        &self * &other
+    }
+}
+
+// Schoolbook product, see `Field::polynomial_product`
+impl<'a, F: Field> Mul<&'a Polynomial<F>> for &'a Polynomial<F>{
+    type Output = Polynomial<F>;
+
+    fn mul(self,other:&Polynomial<F>) -> Self::Output{
+        if self.is_zero() || other.is_zero(){
+            return Polynomial::zero();
+        }
+        Polynomial::new(F::polynomial_product(&self.coefficients,&other.coefficients))
     }
 }
 
-impl fmt::Display for Polynomial<FieldElement>{
+impl<F: Field> fmt::Display for Polynomial<F>{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 
         let length = self.coefficients.len();
@@ -59,19 +117,156 @@ impl fmt::Display for Polynomial<FieldElement>{
 
 impl Polynomial<FieldElement>{
 
-    pub fn evaluate(&self,x: FieldElement) -> FieldElement{
+    // reduces integer coefficients into the field with the given prime
+    pub fn from_integers(coefficients:Vec<BigInt>, prime:&BigInt) -> Self{
+        Polynomial::new(coefficients.into_iter().map(|c| {
+            let c = c % prime;
+            let c = if c.is_negative() { c + prime } else { c };
+            FieldElement::new(c,prime.clone()).unwrap()
+        }).collect())
+    }
 
-        let length = self.coefficients.len();
+    // the polynomial x over the field with the given prime
+    pub fn x(prime:&BigInt) -> Self{
+        Polynomial::x_like(&FieldElement::zero(prime.clone()))
+    }
 
-        let mut sum = BigInt::from(0);
+}
 
-        for i in 0..length{
-            sum += self.coefficients[i].get_number() * x.get_number();
 
+impl<F: Field> Polynomial<F>{
+
+    pub fn new(mut coefficients:Vec<F>) -> Self{
+        while coefficients.last().is_some_and(|c| c.is_zero()){
+            coefficients.pop();
         }
-        let prim = x.get_prime().clone();
-        FieldElement::new(sum % &prim, prim).unwrap()
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Self{
+        Polynomial { coefficients: vec![] }
+    }
 
+    pub fn constant(c:F) -> Self{
+        Polynomial::new(vec![c])
+    }
+
+    // the polynomial x over the field of c
+    pub fn x_like(c:&F) -> Self{
+        Polynomial::new(vec![c.zero_like(),c.one_like()])
+    }
+
+    pub fn get_coefficients(&self) -> &[F]{
+        &self.coefficients
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize>{
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool{
+        self.coefficients.is_empty()
+    }
+
+    pub fn leading_coefficient(&self) -> Option<&F>{
+        self.coefficients.last()
+    }
+
+    pub fn negate(&self) -> Self{
+        Polynomial { coefficients: self.coefficients.iter().map(|c| c.negate()).collect() }
+    }
+
+    pub fn scale(&self, c:&F) -> Self{
+        Polynomial::new(self.coefficients.iter().map(|a| a.clone() * c).collect())
+    }
+
+    pub fn monic(&self) -> Self{
+        match self.leading_coefficient(){
+            Some(lead) => self.scale(&lead.modinv()),
+            None => self.clone(),
+        }
+    }
+
+    // Long division, panics when dividing by the zero polynomial
+    pub fn div_rem(&self, divisor:&Self) -> (Self,Self){
+        let d = divisor.degree().expect("Division by the zero polynomial");
+        if self.degree().is_none_or(|n| n < d){
+            return (Polynomial::zero(),self.clone());
+        }
+        let (quotient,remainder) = F::polynomial_div_rem(&self.coefficients,&divisor.coefficients);
+        (Polynomial::new(quotient),Polynomial::new(remainder))
+    }
+
+    pub fn rem(&self, modulus:&Self) -> Self{
+        self.div_rem(modulus).1
+    }
+
+    // monic greatest common divisor, zero only when both are zero
+    pub fn gcd(&self, other:&Self) -> Self{
+        let (mut a,mut b) = (self.clone(),other.clone());
+        while !b.is_zero(){
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    // (g, s, t) with g = gcd(self, other) monic and s self + t other = g
+    pub fn extended_gcd(&self, other:&Self) -> (Self,Self,Self){
+        let one = match (self.coefficients.first(),other.coefficients.first()){
+            (Some(c),_) | (None,Some(c)) => Polynomial::constant(c.one_like()),
+            (None,None) => return (Polynomial::zero(),Polynomial::zero(),Polynomial::zero()),
+        };
+        let (mut r0,mut r1) = (self.clone(),other.clone());
+        let (mut s0,mut s1) = (one.clone(),Polynomial::zero());
+        let (mut t0,mut t1) = (Polynomial::zero(),one);
+        while !r1.is_zero(){
+            let (q,r) = r0.div_rem(&r1);
+            let s = &s0 - &(&q * &s1);
+            let t = &t0 - &(&q * &t1);
+            (r0,r1) = (r1,r);
+            (s0,s1) = (s1,s);
+            (t0,t1) = (t1,t);
+        }
+        let lead_inverse = r0.leading_coefficient().unwrap().modinv();
+        (r0.scale(&lead_inverse),s0.scale(&lead_inverse),t0.scale(&lead_inverse))
+    }
+
+    // inverse modulo the given polynomial, or None when they share a factor
+    pub fn inverse_mod(&self, modulus:&Self) -> Option<Self>{
+        let (g,s,_) = self.extended_gcd(modulus);
+        if g.degree() != Some(0){
+            return None;
+        }
+        Some(s.rem(modulus))
+    }
+
+    pub fn mul_mod(&self, other:&Self, modulus:&Self) -> Self{
+        (self * other).rem(modulus)
+    }
+
+    pub fn pow_mod(&self, exponent:&BigInt, modulus:&Self) -> Self{
+        let one = modulus.coefficients[0].one_like();
+        let mut result = Polynomial::constant(one).rem(modulus);
+        let base = self.rem(modulus);
+        for i in (0..exponent.bits()).rev(){
+            result = result.mul_mod(&result,modulus);
+            if exponent.bit(i){
+                result = result.mul_mod(&base,modulus);
+            }
+        }
+        result
+    }
+
+    // Horner's rule
+    pub fn evaluate(&self,x: F) -> F{
+        let mut sum = x.zero_like();
+        for c in self.coefficients.iter().rev(){
+            sum = sum * &x + c;
+        }
+        sum
     }
 
 
@@ -79,6 +274,7 @@ impl Polynomial<FieldElement>{
 
 
 
+
 #[test]
 fn polynomial_add(){
     let a1 = FieldElement::new(BigInt::from(3),BigInt::from(7)).unwrap();
@@ -143,5 +339,64 @@ fn evaluate_polynomial(){
     let result = poly1.evaluate(FieldElement::new(BigInt::from(3),BigInt::from(7)).unwrap());
 
     println!("{}",result);
-    
-}
\ No newline at end of file
+    // 3 + 2*3 + 4*9 + 27 = 72 = 2 mod 7
+    assert_eq!(result,FieldElement::new(BigInt::from(2),BigInt::from(7)).unwrap());
+    assert_eq!(Polynomial::zero().evaluate(FieldElement::new(BigInt::from(5),BigInt::from(7)).unwrap()),FieldElement::zero(BigInt::from(7)));
+
+}
+
+
+#[cfg(test)]
+fn poly(coefficients:&[i32], p:i32) -> Polynomial<FieldElement>{
+    Polynomial::from_integers(coefficients.iter().map(|&c| BigInt::from(c)).collect(),&BigInt::from(p))
+}
+
+
+#[test]
+fn polynomial_arithmetic(){
+    let f = poly(&[1,2,3],7);
+    let g = poly(&[6,0,4],7);
+
+    assert_eq!(&f + &g,poly(&[0,2],7));
+    assert_eq!((&f + &g).degree(),Some(1));
+    assert_eq!(&f - &f,Polynomial::zero());
+    assert_eq!(Polynomial::<FieldElement>::zero().degree(),None);
+    // (1 + 2x + 3x^2)(6 + 4x^2) = 6 + 12x + 22x^2 + 8x^3 + 12x^4
+    assert_eq!(&f * &g,poly(&[6,12,22,8,12],7));
+
+    let (q,r) = (&f * &g + poly(&[1,1],7)).div_rem(&g);
+    assert_eq!((q,r),(f.clone(),poly(&[1,1],7)));
+}
+
+
+#[test]
+fn polynomial_gcd_and_inverse(){
+    // (x - 1)(x - 2) and (x - 1)(x + 3) over GF(11)
+    let f = poly(&[2,-3,1],11);
+    let g = poly(&[-3,2,1],11);
+    assert_eq!(f.gcd(&g),poly(&[-1,1],11));
+
+    let (d,s,t) = f.extended_gcd(&g);
+    assert_eq!(&(&s * &f) + &(&t * &g),d);
+
+    // x^2 + 1 is irreducible mod 11, so everything nonzero of lower degree is invertible
+    let modulus = poly(&[1,0,1],11);
+    let a = poly(&[3,5],11);
+    let inverse = a.inverse_mod(&modulus).unwrap();
+    assert_eq!(a.mul_mod(&inverse,&modulus),poly(&[1],11));
+    assert!(poly(&[-1,1],11).inverse_mod(&f).is_none());
+}
+
+
+#[test]
+fn polynomial_pow_mod(){
+    // x^p = x modulo any polynomial dividing x^p - x
+    let modulus = poly(&[0,-1,0,0,0,0,0,1],7);
+    let x = Polynomial::x(&BigInt::from(7));
+    assert_eq!(x.pow_mod(&BigInt::from(7),&modulus),x);
+    // in GF(49) = GF(7)[x] / (x^2 + 1), x has order 4
+    let modulus = poly(&[1,0,1],7);
+    assert_eq!(x.pow_mod(&BigInt::from(4),&modulus),poly(&[1],7));
+    assert_eq!(x.pow_mod(&BigInt::from(2),&modulus),poly(&[-1],7));
+}
+